@@ -1,7 +1,16 @@
-use bevy::prelude::*;
+use std::marker::PhantomData;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::BoxedFuture,
+};
 use bevy_asset_loader::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
 
-use crate::warrior::{DamagedState, WarriorPositionState};
+use crate::game_state::AppState;
+use crate::warrior::{CharacterData, DamagedState, MoveList, WarriorKind, WarriorPositionState};
 
 // tamanho de cada sprite no atlas eh de 250x450
 pub const WARRIOR_SPRITE_TILE_PROPORTION: f32 = 250. / 450.;
@@ -18,7 +27,11 @@ impl Plugin for AssetsPlugin {
     fn build(&self, app: &mut App) {
         app
             // .add_systems(PreStartup, (load_assets,));
-            .init_collection::<WarriorAssets>();
+            .init_asset::<MoveList>()
+            .init_asset::<CharacterData>()
+            .register_asset_loader(RonAssetLoader::<MoveList>::new(&["moves.ron"]))
+            .register_asset_loader(RonAssetLoader::<CharacterData>::new(&["character.ron"]))
+            .add_collection_to_loading_state::<_, WarriorAssets>(AppState::Loading);
     }
 }
 
@@ -36,6 +49,112 @@ pub struct WarriorAssets {
     ))]
     #[asset(path = "jotaile/jotaile_atlas.png")]
     pub jotaile_sprites: Handle<TextureAtlas>,
+    #[asset(path = "jotaile/jotaile.moves.ron")]
+    pub jotaile_moves: Handle<MoveList>,
+    #[asset(path = "jotaile/jotaile.character.ron")]
+    pub jotaile_character: Handle<CharacterData>,
+    #[asset(path = "ed/ed.moves.ron")]
+    pub ed_moves: Handle<MoveList>,
+    #[asset(path = "ed/ed.character.ron")]
+    pub ed_character: Handle<CharacterData>,
+    #[asset(path = "rod/rod.moves.ron")]
+    pub rod_moves: Handle<MoveList>,
+    #[asset(path = "rod/rod.character.ron")]
+    pub rod_character: Handle<CharacterData>,
+    #[asset(path = "turi/turi.moves.ron")]
+    pub turi_moves: Handle<MoveList>,
+    #[asset(path = "turi/turi.character.ron")]
+    pub turi_character: Handle<CharacterData>,
+    #[asset(path = "fred/fred.moves.ron")]
+    pub fred_moves: Handle<MoveList>,
+    #[asset(path = "fred/fred.character.ron")]
+    pub fred_character: Handle<CharacterData>,
+}
+
+/// Everything a warrior of the roster is spawned with
+pub struct RosterEntry {
+    pub sprites: Handle<TextureAtlas>,
+    pub moves: Handle<MoveList>,
+    pub character: Handle<CharacterData>,
+}
+
+impl WarriorAssets {
+    pub fn roster_entry(&self, warrior_kind: &WarriorKind) -> RosterEntry {
+        // the roster shares jotaile's sprites until the others have their own
+        let (sprites, moves, character) = match warrior_kind {
+            WarriorKind::Jotaile => (
+                &self.jotaile_sprites,
+                &self.jotaile_moves,
+                &self.jotaile_character,
+            ),
+            WarriorKind::Ed => (&self.jotaile_sprites, &self.ed_moves, &self.ed_character),
+            WarriorKind::Rod => (&self.jotaile_sprites, &self.rod_moves, &self.rod_character),
+            WarriorKind::Turi => (
+                &self.jotaile_sprites,
+                &self.turi_moves,
+                &self.turi_character,
+            ),
+            WarriorKind::Fred => (
+                &self.jotaile_sprites,
+                &self.fred_moves,
+                &self.fred_character,
+            ),
+        };
+
+        RosterEntry {
+            sprites: sprites.clone(),
+            moves: moves.clone(),
+            character: character.clone(),
+        }
+    }
+}
+
+/// Loads data assets written in RON, each type gets its own compound extension
+pub struct RonAssetLoader<A> {
+    extensions: &'static [&'static str],
+    _asset: PhantomData<fn() -> A>,
+}
+
+impl<A> RonAssetLoader<A> {
+    pub fn new(extensions: &'static [&'static str]) -> Self {
+        RonAssetLoader {
+            extensions,
+            _asset: PhantomData,
+        }
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum RonAssetLoaderError {
+    #[error("Could not load asset: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse asset: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl<A: Asset + for<'de> Deserialize<'de>> AssetLoader for RonAssetLoader<A> {
+    type Asset = A;
+    type Settings = ();
+    type Error = RonAssetLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+
+            Ok(ron::de::from_bytes::<A>(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.extensions
+    }
 }
 
 pub trait IncrementSpriteIndex {
@@ -53,14 +172,14 @@ impl IncrementSpriteIndex for TextureAtlasSprite {
         damaged_state: &DamagedState,
     ) {
         let column_offset = match damaged_state {
-            DamagedState::None => 0,
+            DamagedState::None | DamagedState::Blocked => 0,
             _ => ATLAS_COLUMNS_AMOUNT,
         };
 
         let sprites_idx = match warrior_position_state {
             WarriorPositionState::Idle | WarriorPositionState::Walking => vec![0, 1],
             WarriorPositionState::Jumping => vec![3],
-            WarriorPositionState::Crouching => vec![2],
+            WarriorPositionState::Crouching | WarriorPositionState::GettingUp => vec![2],
             WarriorPositionState::Fallen => vec![4],
         }
         .iter()
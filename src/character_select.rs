@@ -0,0 +1,371 @@
+use bevy::prelude::*;
+
+use crate::assets::{WarriorAssets, WARRIOR_IN_GAME_SPRITE_SIZE};
+use crate::game_state::{AppState, DespawnOnExit};
+use crate::input::{ActionState, WarriorAction};
+use crate::player::PlayerId;
+use crate::warrior::{SpriteAnimationTimer, WarriorKind};
+
+const SELECT_BACKGROUND_COLOR: Color = Color::rgb(0.1, 0.1, 0.15);
+const ROSTER_CARD_COLOR: Color = Color::rgb(0.2, 0.2, 0.3);
+const PLAYER_ONE_CURSOR_COLOR: Color = Color::rgb(0.2, 0.5, 1.);
+const PLAYER_TWO_CURSOR_COLOR: Color = Color::rgb(1., 0.3, 0.2);
+const BOTH_CURSORS_COLOR: Color = Color::rgb(0.8, 0.3, 0.9);
+const PREVIEW_ANIMATION_SECONDS: f32 = 0.5;
+
+pub struct CharacterSelectPlugin;
+
+impl Plugin for CharacterSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectedWarriors>()
+            .register_type::<SelectCursor>()
+            .add_systems(OnEnter(AppState::CharacterSelect), spawn_character_select)
+            .add_systems(
+                Update,
+                (
+                    move_select_cursors,
+                    highlight_roster_cards.after(move_select_cursors),
+                    update_warrior_previews.after(move_select_cursors),
+                    animate_warrior_previews,
+                    start_fight.after(move_select_cursors),
+                )
+                    .run_if(in_state(AppState::CharacterSelect)),
+            );
+    }
+}
+
+/// Warriors confirmed on the character select, spawned when the fight starts
+#[derive(Debug, Resource)]
+pub struct SelectedWarriors {
+    pub player_one: WarriorKind,
+    pub player_two: WarriorKind,
+}
+
+impl Default for SelectedWarriors {
+    fn default() -> Self {
+        SelectedWarriors {
+            player_one: WarriorKind::Jotaile,
+            player_two: WarriorKind::Ed,
+        }
+    }
+}
+
+impl SelectedWarriors {
+    pub fn get(&self, player_id: &PlayerId) -> WarriorKind {
+        match player_id {
+            PlayerId::One => self.player_one,
+            PlayerId::Two => self.player_two,
+        }
+    }
+
+    fn set(&mut self, player_id: &PlayerId, warrior_kind: WarriorKind) {
+        match player_id {
+            PlayerId::One => self.player_one = warrior_kind,
+            PlayerId::Two => self.player_two = warrior_kind,
+        }
+    }
+}
+
+/// Moved over the roster by the player of its [`PlayerId`]
+#[derive(Debug, Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct SelectCursor {
+    pub roster_index: usize,
+    pub is_confirmed: bool,
+}
+
+impl SelectCursor {
+    pub fn warrior_kind(&self) -> WarriorKind {
+        WarriorKind::ALL[self.roster_index]
+    }
+}
+
+#[derive(Debug, Component)]
+pub struct RosterCard(pub WarriorKind);
+
+/// Idle animation of the warrior under the player's cursor
+#[derive(Debug, Component)]
+pub struct WarriorPreview(pub PlayerId);
+
+#[derive(Debug, Component)]
+pub struct WarriorPreviewName(pub PlayerId);
+
+pub fn spawn_character_select(
+    mut commands: Commands,
+    selected_warriors: Res<SelectedWarriors>,
+    warrior_assets: Res<WarriorAssets>,
+) {
+    commands
+        .spawn((
+            Name::new("Character Select"),
+            DespawnOnExit(AppState::CharacterSelect),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::SpaceBetween,
+                    padding: UiRect::all(Val::Px(16.)),
+                    ..default()
+                },
+                background_color: SELECT_BACKGROUND_COLOR.into(),
+                z_index: ZIndex::Global(5),
+                ..default()
+            },
+        ))
+        .with_children(|select| {
+            select.spawn(TextBundle::from_section(
+                "Choose your warrior - Left/Right to move, Light to confirm, Heavy to cancel",
+                TextStyle {
+                    font_size: 20.,
+                    ..default()
+                },
+            ));
+
+            select
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.),
+                        justify_content: JustifyContent::SpaceAround,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|previews| {
+                    for player_id in [PlayerId::One, PlayerId::Two] {
+                        let warrior_kind = selected_warriors.get(&player_id);
+                        spawn_player_preview(previews, player_id, warrior_kind, &warrior_assets);
+                    }
+                });
+
+            select
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(12.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|roster| {
+                    for warrior_kind in WarriorKind::ALL {
+                        roster
+                            .spawn((
+                                RosterCard(warrior_kind),
+                                NodeBundle {
+                                    style: Style {
+                                        width: Val::Px(120.),
+                                        justify_content: JustifyContent::Center,
+                                        padding: UiRect::all(Val::Px(8.)),
+                                        border: UiRect::all(Val::Px(4.)),
+                                        ..default()
+                                    },
+                                    background_color: ROSTER_CARD_COLOR.into(),
+                                    ..default()
+                                },
+                            ))
+                            .with_children(|card| {
+                                card.spawn(TextBundle::from_section(
+                                    warrior_kind.name(),
+                                    TextStyle {
+                                        font_size: 18.,
+                                        ..default()
+                                    },
+                                ));
+                            });
+                    }
+                });
+        });
+}
+
+fn spawn_player_preview(
+    parent: &mut ChildBuilder,
+    player_id: PlayerId,
+    warrior_kind: WarriorKind,
+    warrior_assets: &WarriorAssets,
+) {
+    let roster_index = WarriorKind::ALL
+        .iter()
+        .position(|roster_kind| *roster_kind == warrior_kind)
+        .unwrap_or_default();
+
+    parent
+        .spawn((
+            player_id,
+            ActionState::default(),
+            SelectCursor {
+                roster_index,
+                is_confirmed: false,
+            },
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(8.),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|preview| {
+            preview.spawn(TextBundle::from_section(
+                format!("{player_id:?}"),
+                TextStyle {
+                    font_size: 18.,
+                    color: player_cursor_color(&player_id),
+                    ..default()
+                },
+            ));
+
+            preview.spawn((
+                WarriorPreview(player_id),
+                SpriteAnimationTimer {
+                    timer: Timer::from_seconds(PREVIEW_ANIMATION_SECONDS, TimerMode::Repeating),
+                },
+                AtlasImageBundle {
+                    style: Style {
+                        width: Val::Px(WARRIOR_IN_GAME_SPRITE_SIZE.x),
+                        height: Val::Px(WARRIOR_IN_GAME_SPRITE_SIZE.y),
+                        ..default()
+                    },
+                    texture_atlas: warrior_assets.roster_entry(&warrior_kind).sprites,
+                    texture_atlas_image: UiTextureAtlasImage {
+                        // players look at each other like when the fight starts
+                        flip_x: player_id == PlayerId::Two,
+                        ..default()
+                    },
+                    ..default()
+                },
+            ));
+
+            preview.spawn((
+                WarriorPreviewName(player_id),
+                TextBundle::from_section(
+                    warrior_kind.name(),
+                    TextStyle {
+                        font_size: 24.,
+                        ..default()
+                    },
+                ),
+            ));
+        });
+}
+
+pub fn move_select_cursors(mut cursors: Query<(&ActionState, &mut SelectCursor)>) {
+    let roster_size = WarriorKind::ALL.len();
+
+    for (action_state, mut cursor) in &mut cursors {
+        let just_pressed = action_state.just_pressed_actions();
+
+        if cursor.is_confirmed {
+            if just_pressed.contains(&WarriorAction::HeavyAttack) {
+                cursor.is_confirmed = false;
+            }
+            continue;
+        }
+
+        if just_pressed.contains(&WarriorAction::LightAttack) {
+            cursor.is_confirmed = true;
+        } else if just_pressed.contains(&WarriorAction::Left) {
+            cursor.roster_index = (cursor.roster_index + roster_size - 1) % roster_size;
+        } else if just_pressed.contains(&WarriorAction::Right) {
+            cursor.roster_index = (cursor.roster_index + 1) % roster_size;
+        }
+    }
+}
+
+pub fn highlight_roster_cards(
+    cursors: Query<(&PlayerId, &SelectCursor)>,
+    mut roster_cards: Query<(&RosterCard, &mut BorderColor)>,
+) {
+    for (roster_card, mut border_color) in &mut roster_cards {
+        let hovering_players: Vec<&PlayerId> = cursors
+            .iter()
+            .filter(|(_, cursor)| cursor.warrior_kind() == roster_card.0)
+            .map(|(player_id, _)| player_id)
+            .collect();
+
+        *border_color = match hovering_players.as_slice() {
+            [] => Color::NONE,
+            [player_id] => player_cursor_color(player_id),
+            _ => BOTH_CURSORS_COLOR,
+        }
+        .into();
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn update_warrior_previews(
+    cursors: Query<(&PlayerId, &SelectCursor), Changed<SelectCursor>>,
+    mut previews: Query<(
+        &WarriorPreview,
+        &mut Handle<TextureAtlas>,
+        &mut UiTextureAtlasImage,
+    )>,
+    mut preview_names: Query<(&WarriorPreviewName, &mut Text)>,
+    warrior_assets: Res<WarriorAssets>,
+) {
+    for (player_id, cursor) in &cursors {
+        let warrior_kind = cursor.warrior_kind();
+
+        for (preview, mut texture_atlas, mut atlas_image) in &mut previews {
+            if preview.0 != *player_id {
+                continue;
+            }
+
+            *texture_atlas = warrior_assets.roster_entry(&warrior_kind).sprites;
+            atlas_image.index = 0;
+        }
+
+        for (preview_name, mut text) in &mut preview_names {
+            if preview_name.0 != *player_id {
+                continue;
+            }
+
+            text.sections[0].value = if cursor.is_confirmed {
+                format!("{} - ready!", warrior_kind.name())
+            } else {
+                warrior_kind.name().to_string()
+            };
+        }
+    }
+}
+
+/// Alternates between the idle sprites, the first two of the atlas
+pub fn animate_warrior_previews(
+    time: Res<Time>,
+    mut previews: Query<
+        (&mut SpriteAnimationTimer, &mut UiTextureAtlasImage),
+        With<WarriorPreview>,
+    >,
+) {
+    for (mut animation_timer, mut atlas_image) in &mut previews {
+        if animation_timer.timer.tick(time.delta()).just_finished() {
+            atlas_image.index = (atlas_image.index + 1) % 2;
+        }
+    }
+}
+
+pub fn start_fight(
+    cursors: Query<(&PlayerId, &SelectCursor)>,
+    mut selected_warriors: ResMut<SelectedWarriors>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if cursors.is_empty() || cursors.iter().any(|(_, cursor)| !cursor.is_confirmed) {
+        return;
+    }
+
+    for (player_id, cursor) in &cursors {
+        selected_warriors.set(player_id, cursor.warrior_kind());
+    }
+
+    next_app_state.set(AppState::Fight);
+}
+
+fn player_cursor_color(player_id: &PlayerId) -> Color {
+    match player_id {
+        PlayerId::One => PLAYER_ONE_CURSOR_COLOR,
+        PlayerId::Two => PLAYER_TWO_CURSOR_COLOR,
+    }
+}
@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+use bevy_asset_loader::prelude::*;
+
+const LOADING_TEXT_COLOR: Color = Color::rgb(0.8, 0.8, 0.9);
+
+pub struct GameStatePlugin;
+
+impl Plugin for GameStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<AppState>()
+            .add_state::<PauseState>()
+            .add_loading_state(
+                LoadingState::new(AppState::Loading).continue_to_state(AppState::MainMenu),
+            )
+            .configure_sets(
+                Update,
+                FightSet.run_if(in_state(AppState::Fight).and_then(in_state(PauseState::Running))),
+            )
+            .add_systems(OnEnter(AppState::Loading), spawn_loading_screen);
+
+        for app_state in AppState::ALL {
+            app.add_systems(OnExit(app_state), despawn_state_entities(app_state));
+        }
+
+        app.add_systems(
+            OnExit(PauseState::Paused),
+            despawn_state_entities(PauseState::Paused),
+        );
+    }
+}
+
+/// Screens of the game, each plugin scopes its systems and entities to them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, States)]
+pub enum AppState {
+    /// Asset collections are loaded before anything uses them
+    #[default]
+    Loading,
+    MainMenu,
+    CharacterSelect,
+    Fight,
+    Results,
+}
+
+impl AppState {
+    pub const ALL: [AppState; 5] = [
+        AppState::Loading,
+        AppState::MainMenu,
+        AppState::CharacterSelect,
+        AppState::Fight,
+        AppState::Results,
+    ];
+}
+
+/// Freezes the fight where it is while paused
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, States)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+}
+
+/// Gameplay systems, only running while fighting and not paused
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemSet)]
+pub struct FightSet;
+
+/// Despawned with its children when the game leaves this state
+#[derive(Debug, Component)]
+pub struct DespawnOnExit<S: States = AppState>(pub S);
+
+fn despawn_state_entities<S: States>(
+    state: S,
+) -> impl FnMut(Commands, Query<(Entity, &DespawnOnExit<S>)>) {
+    move |mut commands, scoped_entities| {
+        for (entity, despawn_on_exit) in &scoped_entities {
+            if despawn_on_exit.0 == state {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+pub fn spawn_loading_screen(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Loading Screen"),
+            DespawnOnExit(AppState::Loading),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|loading| {
+            loading.spawn(TextBundle::from_section(
+                "Loading...",
+                TextStyle {
+                    font_size: 32.,
+                    color: LOADING_TEXT_COLOR,
+                    ..default()
+                },
+            ));
+        });
+}
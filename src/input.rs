@@ -0,0 +1,157 @@
+use bevy::{input::InputSystem, prelude::*, utils::HashSet};
+use serde::{Deserialize, Serialize};
+
+use crate::game_state::PauseState;
+use crate::player::PlayerId;
+
+mod bindings;
+mod buffer;
+mod gamepad;
+mod menu;
+mod motion;
+
+pub use self::{bindings::*, buffer::*, gamepad::*, menu::*, motion::*};
+
+pub struct PlayerInputPlugin;
+
+impl Plugin for PlayerInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(InputBindings::load())
+            .init_resource::<BindingsMenu>()
+            .init_resource::<PlayerGamepads>()
+            .init_resource::<MotionLeniency>()
+            .init_resource::<InputFrameCount>()
+            .register_type::<MotionLeniency>()
+            .register_type::<WarriorAction>()
+            .register_type::<ActionState>()
+            .register_type::<InputBuffer>()
+            .add_systems(Startup, spawn_bindings_menu)
+            .add_systems(
+                PreUpdate,
+                (
+                    assign_gamepads.after(InputSystem),
+                    update_actions_state.after(assign_gamepads),
+                    count_input_frames,
+                    record_input_buffers
+                        .after(update_actions_state)
+                        .after(count_input_frames),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    toggle_bindings_menu,
+                    show_bindings_menu
+                        .run_if(resource_changed::<BindingsMenu>())
+                        .after(toggle_bindings_menu),
+                    select_binding_to_edit,
+                    rebind_waiting_action.after(select_binding_to_edit),
+                    update_bindings_menu_texts
+                        .run_if(
+                            resource_changed::<InputBindings>()
+                                .or_else(resource_changed::<BindingsMenu>()),
+                        )
+                        .after(toggle_bindings_menu)
+                        .after(rebind_waiting_action),
+                ),
+            );
+    }
+}
+
+/// What a player wants the warrior to do, whatever key or button is bound to it
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Reflect, Serialize, Deserialize,
+)]
+pub enum WarriorAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    LightAttack,
+    HeavyAttack,
+    Block,
+    Throw,
+}
+
+impl WarriorAction {
+    pub const ALL: [WarriorAction; 8] = [
+        WarriorAction::Up,
+        WarriorAction::Down,
+        WarriorAction::Left,
+        WarriorAction::Right,
+        WarriorAction::LightAttack,
+        WarriorAction::HeavyAttack,
+        WarriorAction::Block,
+        WarriorAction::Throw,
+    ];
+}
+
+/// Actions of the player controlling the warrior on this frame
+#[derive(Debug, Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct ActionState {
+    pressed: HashSet<WarriorAction>,
+    just_pressed: HashSet<WarriorAction>,
+    just_released: HashSet<WarriorAction>,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: WarriorAction) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: WarriorAction) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    pub fn just_released(&self, action: WarriorAction) -> bool {
+        self.just_released.contains(&action)
+    }
+
+    pub fn pressed_actions(&self) -> &HashSet<WarriorAction> {
+        &self.pressed
+    }
+
+    pub fn just_pressed_actions(&self) -> &HashSet<WarriorAction> {
+        &self.just_pressed
+    }
+
+    fn update(&mut self, pressed: HashSet<WarriorAction>) {
+        self.just_pressed = pressed.difference(&self.pressed).copied().collect();
+        self.just_released = self.pressed.difference(&pressed).copied().collect();
+        self.pressed = pressed;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_actions_state(
+    keyboard: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    player_gamepads: Res<PlayerGamepads>,
+    bindings: Res<InputBindings>,
+    bindings_menu: Res<BindingsMenu>,
+    pause_state: Res<State<PauseState>>,
+    mut players: Query<(&PlayerId, &mut ActionState)>,
+) {
+    for (player_id, mut action_state) in &mut players {
+        // keys pressed while rebinding or paused must not move the warriors
+        if bindings_menu.is_open || *pause_state == PauseState::Paused {
+            action_state.update(HashSet::default());
+            continue;
+        }
+
+        let player_bindings = bindings.player(player_id);
+        let mut pressed = player_bindings.pressed_actions(&keyboard);
+
+        if let Some(gamepad) = player_gamepads.get(player_id) {
+            pressed.extend(player_bindings.pressed_gamepad_actions(&gamepad_buttons, gamepad));
+            pressed.extend(stick_direction_actions(gamepad_left_stick(
+                &gamepad_axes,
+                gamepad,
+            )));
+        }
+
+        action_state.update(pressed);
+    }
+}
@@ -0,0 +1,187 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use bevy::{prelude::*, utils::HashSet};
+use serde::{Deserialize, Serialize};
+
+use crate::player::PlayerId;
+
+use super::WarriorAction;
+
+/// Tracked defaults shipped with the game
+pub const BINDINGS_CONFIG_PATH: &str = "config/input_bindings.ron";
+/// Untracked overrides saved by the bindings menu, read before the defaults
+pub const USER_BINDINGS_CONFIG_PATH: &str = "config/input_bindings.user.ron";
+
+#[derive(Debug, Resource, Clone, Serialize, Deserialize)]
+pub struct InputBindings {
+    pub player_one: PlayerBindings,
+    pub player_two: PlayerBindings,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlayerBindings {
+    pub keys: BTreeMap<WarriorAction, Vec<KeyCode>>,
+    #[serde(default)]
+    pub gamepad_buttons: BTreeMap<WarriorAction, Vec<GamepadButtonType>>,
+}
+
+impl InputBindings {
+    /// Falls back to the shipped config and then to the default bindings when missing or invalid
+    pub fn load() -> Self {
+        [USER_BINDINGS_CONFIG_PATH, BINDINGS_CONFIG_PATH]
+            .into_iter()
+            .find_map(InputBindings::read)
+            .unwrap_or_else(|| {
+                info!("Using default input bindings");
+                InputBindings::default()
+            })
+    }
+
+    fn read(path: &str) -> Option<Self> {
+        let config = fs::read_to_string(path)
+            .map_err(|error| info!("Could not read {path}: {error}"))
+            .ok()?;
+
+        ron::from_str(&config)
+            .map_err(|error| warn!("Could not parse {path}: {error}"))
+            .ok()
+    }
+
+    pub fn save(&self) {
+        let config = match ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()) {
+            Ok(config) => config,
+            Err(error) => {
+                warn!("Could not serialize input bindings: {error}");
+                return;
+            }
+        };
+
+        if let Some(config_dir) = Path::new(USER_BINDINGS_CONFIG_PATH).parent() {
+            if let Err(error) = fs::create_dir_all(config_dir) {
+                warn!("Could not create {}: {error}", config_dir.display());
+                return;
+            }
+        }
+
+        if let Err(error) = fs::write(USER_BINDINGS_CONFIG_PATH, config) {
+            warn!("Could not save {USER_BINDINGS_CONFIG_PATH}: {error}");
+        }
+    }
+
+    pub fn player(&self, player_id: &PlayerId) -> &PlayerBindings {
+        match player_id {
+            PlayerId::One => &self.player_one,
+            PlayerId::Two => &self.player_two,
+        }
+    }
+
+    pub fn player_mut(&mut self, player_id: &PlayerId) -> &mut PlayerBindings {
+        match player_id {
+            PlayerId::One => &mut self.player_one,
+            PlayerId::Two => &mut self.player_two,
+        }
+    }
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        InputBindings {
+            player_one: PlayerBindings::from_keys([
+                (WarriorAction::Up, KeyCode::W),
+                (WarriorAction::Down, KeyCode::S),
+                (WarriorAction::Left, KeyCode::A),
+                (WarriorAction::Right, KeyCode::D),
+                (WarriorAction::LightAttack, KeyCode::J),
+                (WarriorAction::HeavyAttack, KeyCode::K),
+                (WarriorAction::Block, KeyCode::L),
+                (WarriorAction::Throw, KeyCode::U),
+            ])
+            .with_default_gamepad_buttons(),
+            player_two: PlayerBindings::from_keys([
+                (WarriorAction::Up, KeyCode::Up),
+                (WarriorAction::Down, KeyCode::Down),
+                (WarriorAction::Left, KeyCode::Left),
+                (WarriorAction::Right, KeyCode::Right),
+                (WarriorAction::LightAttack, KeyCode::Numpad1),
+                (WarriorAction::HeavyAttack, KeyCode::Numpad2),
+                (WarriorAction::Block, KeyCode::Numpad3),
+                (WarriorAction::Throw, KeyCode::Numpad4),
+            ])
+            .with_default_gamepad_buttons(),
+        }
+    }
+}
+
+impl PlayerBindings {
+    fn from_keys(keys: impl IntoIterator<Item = (WarriorAction, KeyCode)>) -> Self {
+        PlayerBindings {
+            keys: keys
+                .into_iter()
+                .map(|(action, key)| (action, vec![key]))
+                .collect(),
+            ..default()
+        }
+    }
+
+    fn with_default_gamepad_buttons(mut self) -> Self {
+        self.gamepad_buttons = [
+            (WarriorAction::Up, GamepadButtonType::DPadUp),
+            (WarriorAction::Down, GamepadButtonType::DPadDown),
+            (WarriorAction::Left, GamepadButtonType::DPadLeft),
+            (WarriorAction::Right, GamepadButtonType::DPadRight),
+            (WarriorAction::LightAttack, GamepadButtonType::West),
+            (WarriorAction::HeavyAttack, GamepadButtonType::North),
+            (WarriorAction::Block, GamepadButtonType::RightTrigger),
+            (WarriorAction::Throw, GamepadButtonType::East),
+        ]
+        .into_iter()
+        .map(|(action, button)| (action, vec![button]))
+        .collect();
+
+        self
+    }
+
+    pub fn pressed_actions(&self, keyboard: &Input<KeyCode>) -> HashSet<WarriorAction> {
+        self.keys
+            .iter()
+            .filter(|(_, keys)| keyboard.any_pressed(keys.iter().copied()))
+            .map(|(action, _)| *action)
+            .collect()
+    }
+
+    pub fn pressed_gamepad_actions(
+        &self,
+        gamepad_buttons: &Input<GamepadButton>,
+        gamepad: Gamepad,
+    ) -> HashSet<WarriorAction> {
+        self.gamepad_buttons
+            .iter()
+            .filter(|(_, buttons)| {
+                gamepad_buttons.any_pressed(
+                    buttons
+                        .iter()
+                        .map(|button_type| GamepadButton::new(gamepad, *button_type)),
+                )
+            })
+            .map(|(action, _)| *action)
+            .collect()
+    }
+
+    /// A key drives a single action of the player
+    pub fn rebind_key(&mut self, action: WarriorAction, key: KeyCode) {
+        for keys in self.keys.values_mut() {
+            keys.retain(|bound_key| *bound_key != key);
+        }
+
+        self.keys.insert(action, vec![key]);
+    }
+
+    /// A button drives a single action of the player
+    pub fn rebind_gamepad_button(&mut self, action: WarriorAction, button: GamepadButtonType) {
+        for buttons in self.gamepad_buttons.values_mut() {
+            buttons.retain(|bound_button| *bound_button != button);
+        }
+
+        self.gamepad_buttons.insert(action, vec![button]);
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::VecDeque;
+
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::warrior::FRAME_DURATION;
+
+use super::{ActionState, WarriorAction};
+
+/// Frames of history kept for each warrior, enough for motion inputs
+pub const INPUT_BUFFER_FRAMES: u32 = 60;
+/// How early an action can be pressed and still come out once the warrior can act
+pub const BUFFERED_INPUT_FRAMES: u32 = 8;
+
+#[derive(Debug, Clone, Reflect, Default)]
+pub struct InputFrame {
+    pub frame: u32,
+    pub pressed: HashSet<WarriorAction>,
+    pub just_pressed: HashSet<WarriorAction>,
+}
+
+/// Game time counted in frames of [`FRAME_DURATION`], so the input windows last the same at any
+/// frame rate and stop while paused
+#[derive(Debug, Resource, Default)]
+pub struct InputFrameCount(pub u32);
+
+/// Ring buffer with the latest input frames of the warrior, newest last
+#[derive(Debug, Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct InputBuffer {
+    frames: VecDeque<InputFrame>,
+}
+
+impl InputBuffer {
+    pub fn push(&mut self, input_frame: InputFrame) {
+        while self.frames.front().is_some_and(|oldest_frame| {
+            input_frame.frame.wrapping_sub(oldest_frame.frame) >= INPUT_BUFFER_FRAMES
+        }) {
+            self.frames.pop_front();
+        }
+
+        self.frames.push_back(input_frame);
+    }
+
+    /// Frames recorded in the last `frames` frames, newest first
+    pub fn recent(&self, frames: u32, current_frame: u32) -> impl Iterator<Item = &InputFrame> {
+        self.frames
+            .iter()
+            .rev()
+            .take_while(move |input_frame| current_frame.wrapping_sub(input_frame.frame) < frames)
+    }
+
+    pub fn pressed_within(&self, action: WarriorAction, frames: u32, current_frame: u32) -> bool {
+        self.recent(frames, current_frame)
+            .any(|input_frame| input_frame.just_pressed.contains(&action))
+    }
+
+    /// Same as [`InputBuffer::pressed_within`], but the press can't be used again
+    pub fn consume_pressed_within(
+        &mut self,
+        action: WarriorAction,
+        frames: u32,
+        current_frame: u32,
+    ) -> bool {
+        let mut consumed = false;
+
+        for input_frame in self.frames.iter_mut().rev() {
+            if current_frame.wrapping_sub(input_frame.frame) >= frames {
+                break;
+            }
+
+            consumed |= input_frame.just_pressed.remove(&action);
+        }
+
+        consumed
+    }
+
+    /// Forgets the directions held so far, so the motion of a special can't make another one
+    pub fn consume_motions(&mut self) {
+        for input_frame in &mut self.frames {
+            input_frame.pressed.clear();
+        }
+    }
+}
+
+pub fn count_input_frames(time: Res<Time>, mut input_frame_count: ResMut<InputFrameCount>) {
+    input_frame_count.0 = (time.elapsed_seconds_f64() / FRAME_DURATION as f64) as u32;
+}
+
+pub fn record_input_buffers(
+    input_frame_count: Res<InputFrameCount>,
+    mut players: Query<(&ActionState, &mut InputBuffer)>,
+) {
+    for (action_state, mut input_buffer) in &mut players {
+        input_buffer.push(InputFrame {
+            frame: input_frame_count.0,
+            pressed: action_state.pressed_actions().clone(),
+            just_pressed: action_state.just_pressed_actions().clone(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// One input frame per frame up to `last_frame`, the action is only pressed on `pressed_frame`
+    fn buffer_with_press(pressed_frame: u32, last_frame: u32) -> InputBuffer {
+        let mut input_buffer = InputBuffer::default();
+
+        for frame in 0..=last_frame {
+            let mut just_pressed = HashSet::default();
+            if frame == pressed_frame {
+                just_pressed.insert(WarriorAction::LightAttack);
+            }
+
+            input_buffer.push(InputFrame {
+                frame,
+                pressed: just_pressed.clone(),
+                just_pressed,
+            });
+        }
+
+        input_buffer
+    }
+
+    #[test]
+    fn find_press_inside_the_window() {
+        let input_buffer = buffer_with_press(10, 10 + BUFFERED_INPUT_FRAMES - 1);
+
+        assert!(input_buffer.pressed_within(
+            WarriorAction::LightAttack,
+            BUFFERED_INPUT_FRAMES,
+            10 + BUFFERED_INPUT_FRAMES - 1
+        ));
+        assert!(!input_buffer.pressed_within(
+            WarriorAction::HeavyAttack,
+            BUFFERED_INPUT_FRAMES,
+            10 + BUFFERED_INPUT_FRAMES - 1
+        ));
+    }
+
+    #[test]
+    fn miss_press_outside_the_window() {
+        let mut input_buffer = buffer_with_press(10, 10 + BUFFERED_INPUT_FRAMES);
+
+        assert!(!input_buffer.pressed_within(
+            WarriorAction::LightAttack,
+            BUFFERED_INPUT_FRAMES,
+            10 + BUFFERED_INPUT_FRAMES
+        ));
+        assert!(!input_buffer.consume_pressed_within(
+            WarriorAction::LightAttack,
+            BUFFERED_INPUT_FRAMES,
+            10 + BUFFERED_INPUT_FRAMES
+        ));
+        // missing the window doesn't use up the press
+        assert!(input_buffer.pressed_within(
+            WarriorAction::LightAttack,
+            BUFFERED_INPUT_FRAMES + 1,
+            10 + BUFFERED_INPUT_FRAMES
+        ));
+    }
+
+    #[test]
+    fn consume_press_once() {
+        let mut input_buffer = buffer_with_press(10, 12);
+
+        assert!(input_buffer.consume_pressed_within(
+            WarriorAction::LightAttack,
+            BUFFERED_INPUT_FRAMES,
+            12
+        ));
+        assert!(!input_buffer.consume_pressed_within(
+            WarriorAction::LightAttack,
+            BUFFERED_INPUT_FRAMES,
+            12
+        ));
+        assert!(!input_buffer.pressed_within(
+            WarriorAction::LightAttack,
+            BUFFERED_INPUT_FRAMES,
+            12
+        ));
+    }
+
+    #[test]
+    fn evict_frames_older_than_the_buffer() {
+        let last_frame = INPUT_BUFFER_FRAMES + 10;
+        let input_buffer = buffer_with_press(10, last_frame);
+
+        assert_eq!(input_buffer.frames.len(), INPUT_BUFFER_FRAMES as usize);
+        assert_eq!(
+            input_buffer
+                .frames
+                .front()
+                .map(|input_frame| input_frame.frame),
+            Some(last_frame - INPUT_BUFFER_FRAMES + 1)
+        );
+        assert!(!input_buffer.pressed_within(WarriorAction::LightAttack, u32::MAX, last_frame));
+    }
+
+    #[test]
+    fn count_frames_of_game_time() {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .init_resource::<InputFrameCount>()
+            .add_systems(Update, count_input_frames);
+
+        app.world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f32(FRAME_DURATION * 10.5));
+        app.update();
+
+        assert_eq!(app.world.resource::<InputFrameCount>().0, 10);
+    }
+}
@@ -0,0 +1,221 @@
+use std::f32::consts::FRAC_PI_4;
+
+use bevy::{
+    input::gamepad::{GamepadConnection, GamepadConnectionEvent},
+    prelude::*,
+    utils::HashSet,
+};
+
+use crate::player::PlayerId;
+
+use super::WarriorAction;
+
+/// Stick tilt ignored as resting noise
+pub const STICK_DEADZONE: f32 = 0.3;
+
+/// Gamepads driving each player slot, filled as they connect
+#[derive(Debug, Resource, Default)]
+pub struct PlayerGamepads {
+    pub player_one: Option<Gamepad>,
+    pub player_two: Option<Gamepad>,
+}
+
+impl PlayerGamepads {
+    pub fn get(&self, player_id: &PlayerId) -> Option<Gamepad> {
+        match player_id {
+            PlayerId::One => self.player_one,
+            PlayerId::Two => self.player_two,
+        }
+    }
+
+    fn slots_mut(&mut self) -> [(PlayerId, &mut Option<Gamepad>); 2] {
+        [
+            (PlayerId::One, &mut self.player_one),
+            (PlayerId::Two, &mut self.player_two),
+        ]
+    }
+}
+
+/// Gives connected gamepads to the first free player and frees the player when unplugged
+pub fn assign_gamepads(
+    mut player_gamepads: ResMut<PlayerGamepads>,
+    mut connection_events: EventReader<GamepadConnectionEvent>,
+) {
+    for connection_event in connection_events.read() {
+        let gamepad = connection_event.gamepad;
+
+        match connection_event.connection {
+            GamepadConnection::Connected(_) => {
+                if player_gamepads.get(&PlayerId::One) == Some(gamepad)
+                    || player_gamepads.get(&PlayerId::Two) == Some(gamepad)
+                {
+                    continue;
+                }
+
+                if let Some((player_id, slot)) = player_gamepads
+                    .slots_mut()
+                    .into_iter()
+                    .find(|(_, slot)| slot.is_none())
+                {
+                    *slot = Some(gamepad);
+                    info!("{gamepad:?} assigned to player {player_id:?}");
+                }
+            }
+            GamepadConnection::Disconnected => {
+                for (player_id, slot) in player_gamepads.slots_mut() {
+                    if *slot == Some(gamepad) {
+                        *slot = None;
+                        info!("{gamepad:?} unassigned from player {player_id:?}");
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Quantizes the stick into one of 8 directions, none inside the deadzone
+pub fn stick_direction_actions(stick: Vec2) -> HashSet<WarriorAction> {
+    let mut actions = HashSet::default();
+
+    if stick.length() < STICK_DEADZONE {
+        return actions;
+    }
+
+    // 0 is right, counting 45 degrees counter-clockwise up to 7
+    let sector = (stick.y.atan2(stick.x) / FRAC_PI_4).round().rem_euclid(8.) as u8;
+
+    if matches!(sector, 7 | 0 | 1) {
+        actions.insert(WarriorAction::Right);
+    }
+    if matches!(sector, 1..=3) {
+        actions.insert(WarriorAction::Up);
+    }
+    if matches!(sector, 3..=5) {
+        actions.insert(WarriorAction::Left);
+    }
+    if matches!(sector, 5..=7) {
+        actions.insert(WarriorAction::Down);
+    }
+
+    actions
+}
+
+pub fn gamepad_left_stick(axes: &Axis<GamepadAxis>, gamepad: Gamepad) -> Vec2 {
+    Vec2::new(
+        axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+            .unwrap_or_default(),
+        axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY))
+            .unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::{
+        gamepad::{GamepadEvent, GamepadInfo},
+        InputPlugin, InputSystem,
+    };
+
+    use super::*;
+
+    fn gamepads_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin))
+            .init_resource::<PlayerGamepads>()
+            .add_systems(PreUpdate, assign_gamepads.after(InputSystem));
+
+        app
+    }
+
+    fn send_connection(app: &mut App, gamepad_id: usize, is_connected: bool) {
+        let connection = if is_connected {
+            GamepadConnection::Connected(GamepadInfo {
+                name: format!("Gamepad {gamepad_id}"),
+            })
+        } else {
+            GamepadConnection::Disconnected
+        };
+
+        app.world
+            .send_event(GamepadEvent::Connection(GamepadConnectionEvent::new(
+                Gamepad::new(gamepad_id),
+                connection,
+            )));
+        app.update();
+    }
+
+    fn assigned_gamepads(app: &App) -> (Option<Gamepad>, Option<Gamepad>) {
+        let player_gamepads = app.world.resource::<PlayerGamepads>();
+        (player_gamepads.player_one, player_gamepads.player_two)
+    }
+
+    #[test]
+    fn assign_gamepads_in_connection_order() {
+        let mut app = gamepads_app();
+
+        send_connection(&mut app, 0, true);
+        assert_eq!(assigned_gamepads(&app), (Some(Gamepad::new(0)), None));
+
+        send_connection(&mut app, 1, true);
+        assert_eq!(
+            assigned_gamepads(&app),
+            (Some(Gamepad::new(0)), Some(Gamepad::new(1)))
+        );
+
+        // both players have one already
+        send_connection(&mut app, 2, true);
+        assert_eq!(
+            assigned_gamepads(&app),
+            (Some(Gamepad::new(0)), Some(Gamepad::new(1)))
+        );
+    }
+
+    #[test]
+    fn ignore_reconnection_of_assigned_gamepad() {
+        let mut app = gamepads_app();
+
+        send_connection(&mut app, 0, true);
+        send_connection(&mut app, 0, true);
+        assert_eq!(assigned_gamepads(&app), (Some(Gamepad::new(0)), None));
+    }
+
+    #[test]
+    fn reassign_freed_player_on_disconnection() {
+        let mut app = gamepads_app();
+
+        send_connection(&mut app, 0, true);
+        send_connection(&mut app, 1, true);
+
+        send_connection(&mut app, 0, false);
+        assert_eq!(assigned_gamepads(&app), (None, Some(Gamepad::new(1))));
+
+        send_connection(&mut app, 2, true);
+        assert_eq!(
+            assigned_gamepads(&app),
+            (Some(Gamepad::new(2)), Some(Gamepad::new(1)))
+        );
+    }
+
+    #[test]
+    fn ignore_stick_inside_deadzone() {
+        assert!(stick_direction_actions(Vec2::ZERO).is_empty());
+        assert!(stick_direction_actions(Vec2::new(0.29, 0.)).is_empty());
+        assert!(stick_direction_actions(Vec2::new(0.2, -0.2)).is_empty());
+    }
+
+    #[test]
+    fn quantize_stick_outside_deadzone() {
+        assert_eq!(
+            stick_direction_actions(Vec2::new(0.31, 0.)),
+            HashSet::from_iter([WarriorAction::Right])
+        );
+        assert_eq!(
+            stick_direction_actions(Vec2::new(-0.7, -0.7)),
+            HashSet::from_iter([WarriorAction::Left, WarriorAction::Down])
+        );
+        assert_eq!(
+            stick_direction_actions(Vec2::new(0.1, 1.)),
+            HashSet::from_iter([WarriorAction::Up])
+        );
+    }
+}
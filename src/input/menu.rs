@@ -0,0 +1,250 @@
+use bevy::prelude::*;
+
+use crate::player::PlayerId;
+
+use super::{InputBindings, PlayerGamepads, WarriorAction};
+
+pub const BINDINGS_MENU_KEY: KeyCode = KeyCode::F1;
+const CANCEL_REBIND_KEY: KeyCode = KeyCode::Escape;
+
+const MENU_BACKGROUND_COLOR: Color = Color::rgba(0., 0., 0., 0.85);
+const BINDING_BUTTON_COLOR: Color = Color::rgb(0.2, 0.2, 0.3);
+const WAITING_BINDING_BUTTON_COLOR: Color = Color::rgb(0.6, 0.4, 0.1);
+
+#[derive(Debug, Resource, Default)]
+pub struct BindingsMenu {
+    pub is_open: bool,
+    /// Action whose binding will be replaced by the next key or button pressed
+    pub waiting_key: Option<(PlayerId, WarriorAction)>,
+}
+
+#[derive(Debug, Component)]
+pub struct BindingsMenuRoot;
+
+#[derive(Debug, Component)]
+pub struct BindingButton {
+    pub player_id: PlayerId,
+    pub action: WarriorAction,
+}
+
+pub fn spawn_bindings_menu(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Bindings Menu"),
+            BindingsMenuRoot,
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::all(Val::Px(16.)),
+                    row_gap: Val::Px(12.),
+                    ..default()
+                },
+                background_color: MENU_BACKGROUND_COLOR.into(),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+        ))
+        .with_children(|menu| {
+            menu.spawn(TextBundle::from_section(
+                "Controls - click an action and press its new key or button (Esc cancels, F1 closes)",
+                TextStyle {
+                    font_size: 20.,
+                    ..default()
+                },
+            ));
+
+            menu.spawn(NodeBundle {
+                style: Style {
+                    column_gap: Val::Px(48.),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|columns| {
+                for player_id in [PlayerId::One, PlayerId::Two] {
+                    spawn_player_bindings_column(columns, player_id);
+                }
+            });
+        });
+}
+
+fn spawn_player_bindings_column(parent: &mut ChildBuilder, player_id: PlayerId) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|column| {
+            column.spawn(TextBundle::from_section(
+                format!("{player_id:?}"),
+                TextStyle {
+                    font_size: 18.,
+                    ..default()
+                },
+            ));
+
+            for action in WarriorAction::ALL {
+                column
+                    .spawn((
+                        BindingButton { player_id, action },
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(320.),
+                                padding: UiRect::axes(Val::Px(8.), Val::Px(2.)),
+                                ..default()
+                            },
+                            background_color: BINDING_BUTTON_COLOR.into(),
+                            ..default()
+                        },
+                    ))
+                    .with_children(|button| {
+                        button.spawn(TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font_size: 16.,
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+pub fn toggle_bindings_menu(
+    keyboard: Res<Input<KeyCode>>,
+    mut bindings_menu: ResMut<BindingsMenu>,
+) {
+    if !keyboard.just_pressed(BINDINGS_MENU_KEY) {
+        return;
+    }
+
+    bindings_menu.is_open = !bindings_menu.is_open;
+    bindings_menu.waiting_key = None;
+}
+
+/// The menu may also be opened from elsewhere, like the pause menu
+pub fn show_bindings_menu(
+    bindings_menu: Res<BindingsMenu>,
+    mut menu_roots: Query<&mut Style, With<BindingsMenuRoot>>,
+) {
+    for mut menu_style in &mut menu_roots {
+        menu_style.display = if bindings_menu.is_open {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
+
+pub fn select_binding_to_edit(
+    mut bindings_menu: ResMut<BindingsMenu>,
+    binding_buttons: Query<(&Interaction, &BindingButton), Changed<Interaction>>,
+) {
+    for (interaction, binding_button) in &binding_buttons {
+        if *interaction == Interaction::Pressed {
+            bindings_menu.waiting_key = Some((binding_button.player_id, binding_button.action));
+        }
+    }
+}
+
+pub fn rebind_waiting_action(
+    keyboard: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    player_gamepads: Res<PlayerGamepads>,
+    mut bindings_menu: ResMut<BindingsMenu>,
+    mut bindings: ResMut<InputBindings>,
+) {
+    let Some((player_id, action)) = bindings_menu.waiting_key else {
+        return;
+    };
+
+    let player_gamepad = player_gamepads.get(&player_id);
+
+    // only the player's own gamepad can rebind their buttons
+    if let Some(button) = gamepad_buttons
+        .get_just_pressed()
+        .find(|button| Some(button.gamepad) == player_gamepad)
+    {
+        bindings_menu.waiting_key = None;
+        bindings
+            .player_mut(&player_id)
+            .rebind_gamepad_button(action, button.button_type);
+        bindings.save();
+        return;
+    }
+
+    let Some(&key) = keyboard
+        .get_just_pressed()
+        .find(|key| **key != BINDINGS_MENU_KEY)
+    else {
+        return;
+    };
+
+    bindings_menu.waiting_key = None;
+
+    if key == CANCEL_REBIND_KEY {
+        return;
+    }
+
+    bindings.player_mut(&player_id).rebind_key(action, key);
+    bindings.save();
+}
+
+pub fn update_bindings_menu_texts(
+    bindings_menu: Res<BindingsMenu>,
+    bindings: Res<InputBindings>,
+    mut binding_buttons: Query<(&BindingButton, &Children, &mut BackgroundColor)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (binding_button, button_children, mut button_color) in &mut binding_buttons {
+        let is_waiting_key =
+            bindings_menu.waiting_key == Some((binding_button.player_id, binding_button.action));
+
+        *button_color = if is_waiting_key {
+            WAITING_BINDING_BUTTON_COLOR
+        } else {
+            BINDING_BUTTON_COLOR
+        }
+        .into();
+
+        let bindings_text = if is_waiting_key {
+            "press a key or button...".to_string()
+        } else {
+            let player_bindings = bindings.player(&binding_button.player_id);
+            let keys = player_bindings
+                .keys
+                .get(&binding_button.action)
+                .into_iter()
+                .flatten()
+                .map(|key| format!("{key:?}"));
+            let buttons = player_bindings
+                .gamepad_buttons
+                .get(&binding_button.action)
+                .into_iter()
+                .flatten()
+                .map(|button| format!("{button:?}"));
+            let bound: Vec<String> = keys.chain(buttons).collect();
+
+            if bound.is_empty() {
+                "-".to_string()
+            } else {
+                bound.join(" / ")
+            }
+        };
+
+        for &child in button_children {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.sections[0].value = format!("{:?}: {bindings_text}", binding_button.action);
+            }
+        }
+    }
+}
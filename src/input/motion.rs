@@ -0,0 +1,368 @@
+use bevy::{prelude::*, utils::HashSet};
+use serde::Deserialize;
+
+use crate::warrior::FacingPosition;
+
+use super::{InputBuffer, WarriorAction, INPUT_BUFFER_FRAMES};
+
+/// Directional sequences in numpad notation, read as if the warrior was facing right
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Reflect)]
+pub enum Motion {
+    /// 236
+    QuarterCircleForward,
+    /// 214
+    QuarterCircleBack,
+    /// 623
+    DragonPunch,
+    /// [4]6
+    ChargeBackForward,
+}
+
+impl Motion {
+    /// Checked in this order, so overlapping motions pick the hardest one
+    pub const PRIORITY: [Motion; 4] = [
+        Motion::ChargeBackForward,
+        Motion::DragonPunch,
+        Motion::QuarterCircleForward,
+        Motion::QuarterCircleBack,
+    ];
+
+    /// Numpad notation shown on the move list
+    pub fn notation(&self) -> &'static str {
+        match self {
+            Motion::QuarterCircleForward => "236",
+            Motion::QuarterCircleBack => "214",
+            Motion::DragonPunch => "623",
+            Motion::ChargeBackForward => "[4]6",
+        }
+    }
+
+    pub fn is_completed(
+        &self,
+        input_buffer: &InputBuffer,
+        facing_position: &FacingPosition,
+        motion_leniency: &MotionLeniency,
+        current_frame: u32,
+    ) -> bool {
+        // newest first, with how many frames ago each direction was held
+        let directions: Vec<(u32, u8)> = input_buffer
+            .recent(INPUT_BUFFER_FRAMES, current_frame)
+            .map(|input_frame| {
+                (
+                    current_frame.wrapping_sub(input_frame.frame),
+                    numpad_direction(&input_frame.pressed, facing_position),
+                )
+            })
+            .collect();
+
+        match self {
+            Motion::QuarterCircleForward => {
+                completed_sequence(&[2, 3, 6], &directions, motion_leniency)
+            }
+            Motion::QuarterCircleBack => {
+                completed_sequence(&[2, 1, 4], &directions, motion_leniency)
+            }
+            Motion::DragonPunch => completed_sequence(&[6, 2, 3], &directions, motion_leniency),
+            Motion::ChargeBackForward => completed_charge(&directions, motion_leniency),
+        }
+    }
+}
+
+/// Frame windows of the motions, the bigger the easier they come out
+#[derive(Debug, Resource, Reflect)]
+#[reflect(Resource)]
+pub struct MotionLeniency {
+    /// From the first direction of the motion to the last one
+    pub motion_frames: u32,
+    /// From the last direction of the motion to the button
+    pub button_frames: u32,
+    /// Back must be held at least this long before a charge motion
+    pub charge_frames: u32,
+}
+
+impl Default for MotionLeniency {
+    fn default() -> Self {
+        MotionLeniency {
+            motion_frames: 20,
+            button_frames: 12,
+            charge_frames: 30,
+        }
+    }
+}
+
+/// 5 is neutral, 6 is forward and 4 is back relative to where the warrior is facing
+pub fn numpad_direction(pressed: &HashSet<WarriorAction>, facing_position: &FacingPosition) -> u8 {
+    let axis = |positive, negative| {
+        i8::from(pressed.contains(&positive)) - i8::from(pressed.contains(&negative))
+    };
+
+    let forward =
+        axis(WarriorAction::Right, WarriorAction::Left) * facing_position.direction() as i8;
+    let up = axis(WarriorAction::Up, WarriorAction::Down);
+
+    (5 + forward + 3 * up) as u8
+}
+
+/// Matches the sequence from its end, so other directions in between are forgiven
+fn completed_sequence(
+    sequence: &[u8],
+    directions: &[(u32, u8)],
+    motion_leniency: &MotionLeniency,
+) -> bool {
+    let mut remaining = sequence.iter().rev().peekable();
+    let mut last_direction_age = None;
+
+    for &(age, direction) in directions {
+        let Some(&&expected) = remaining.peek() else {
+            break;
+        };
+
+        match last_direction_age {
+            None if age > motion_leniency.button_frames => return false,
+            Some(last_direction_age)
+                if age - last_direction_age > motion_leniency.motion_frames =>
+            {
+                return false
+            }
+            _ => {}
+        }
+
+        if direction == expected {
+            last_direction_age.get_or_insert(age);
+            remaining.next();
+        }
+    }
+
+    remaining.peek().is_none()
+}
+
+fn completed_charge(directions: &[(u32, u8)], motion_leniency: &MotionLeniency) -> bool {
+    let is_back = |direction: u8| matches!(direction, 1 | 4 | 7);
+    let is_forward = |direction: u8| matches!(direction, 3 | 6 | 9);
+
+    let Some(forward_index) = directions.iter().position(|&(age, direction)| {
+        age <= motion_leniency.button_frames && is_forward(direction)
+    }) else {
+        return false;
+    };
+    let forward_age = directions[forward_index].0;
+
+    let mut charge_ages = directions[forward_index..]
+        .iter()
+        .skip_while(|&&(age, direction)| {
+            !is_back(direction) && age - forward_age <= motion_leniency.button_frames
+        })
+        .take_while(|&&(_, direction)| is_back(direction))
+        .map(|&(age, _)| age);
+
+    let Some(charge_end_age) = charge_ages.next() else {
+        return false;
+    };
+    let charge_start_age = charge_ages.last().unwrap_or(charge_end_age);
+
+    charge_start_age - charge_end_age + 1 >= motion_leniency.charge_frames
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::InputFrame;
+
+    const FACINGS: [FacingPosition; 2] = [FacingPosition::Right, FacingPosition::Left];
+
+    /// Actions held for a numpad direction, mirrored when facing left
+    fn held_actions(direction: u8, facing_position: &FacingPosition) -> HashSet<WarriorAction> {
+        let forward = ((direction - 1) % 3) as i8 - 1;
+        let up = ((direction - 1) / 3) as i8 - 1;
+        let mut pressed = HashSet::default();
+
+        match forward * facing_position.direction() as i8 {
+            1 => pressed.insert(WarriorAction::Right),
+            -1 => pressed.insert(WarriorAction::Left),
+            _ => false,
+        };
+        match up {
+            1 => pressed.insert(WarriorAction::Up),
+            -1 => pressed.insert(WarriorAction::Down),
+            _ => false,
+        };
+
+        pressed
+    }
+
+    /// One input frame per frame, holding each direction for its frames, returns the last frame
+    fn push_directions(
+        input_buffer: &mut InputBuffer,
+        first_frame: u32,
+        directions: &[(u8, u32)],
+        facing_position: &FacingPosition,
+    ) -> u32 {
+        let mut frame = first_frame;
+
+        for &(direction, held_frames) in directions {
+            for _ in 0..held_frames {
+                input_buffer.push(InputFrame {
+                    frame,
+                    pressed: held_actions(direction, facing_position),
+                    just_pressed: HashSet::default(),
+                });
+                frame += 1;
+            }
+        }
+
+        frame - 1
+    }
+
+    fn is_completed_after(
+        motion: Motion,
+        directions: &[(u8, u32)],
+        facing_position: &FacingPosition,
+    ) -> bool {
+        let mut input_buffer = InputBuffer::default();
+        let current_frame = push_directions(&mut input_buffer, 0, directions, facing_position);
+
+        motion.is_completed(
+            &input_buffer,
+            facing_position,
+            &MotionLeniency::default(),
+            current_frame,
+        )
+    }
+
+    #[test]
+    fn complete_quarter_circle_forward() {
+        for facing_position in &FACINGS {
+            assert!(is_completed_after(
+                Motion::QuarterCircleForward,
+                &[(2, 3), (3, 3), (6, 3)],
+                facing_position
+            ));
+        }
+    }
+
+    #[test]
+    fn complete_quarter_circle_back() {
+        for facing_position in &FACINGS {
+            assert!(is_completed_after(
+                Motion::QuarterCircleBack,
+                &[(2, 3), (1, 3), (4, 3)],
+                facing_position
+            ));
+        }
+    }
+
+    #[test]
+    fn complete_dragon_punch() {
+        for facing_position in &FACINGS {
+            assert!(is_completed_after(
+                Motion::DragonPunch,
+                &[(6, 3), (2, 3), (3, 3)],
+                facing_position
+            ));
+        }
+    }
+
+    #[test]
+    fn complete_charge_back_forward() {
+        for facing_position in &FACINGS {
+            assert!(is_completed_after(
+                Motion::ChargeBackForward,
+                &[(4, 35), (6, 2)],
+                facing_position
+            ));
+        }
+    }
+
+    #[test]
+    fn read_motions_relative_to_facing() {
+        let mut input_buffer = InputBuffer::default();
+        let current_frame = push_directions(
+            &mut input_buffer,
+            0,
+            &[(2, 3), (3, 3), (6, 3)],
+            &FacingPosition::Right,
+        );
+
+        // the same stick moves are a quarter circle back for a warrior facing left
+        assert!(Motion::QuarterCircleBack.is_completed(
+            &input_buffer,
+            &FacingPosition::Left,
+            &MotionLeniency::default(),
+            current_frame,
+        ));
+        assert!(!Motion::QuarterCircleForward.is_completed(
+            &input_buffer,
+            &FacingPosition::Left,
+            &MotionLeniency::default(),
+            current_frame,
+        ));
+    }
+
+    #[test]
+    fn expire_motion_after_button_leniency() {
+        for facing_position in &FACINGS {
+            assert!(!is_completed_after(
+                Motion::QuarterCircleForward,
+                &[(2, 3), (3, 3), (6, 3), (5, 15)],
+                facing_position
+            ));
+        }
+    }
+
+    #[test]
+    fn expire_motion_after_motion_leniency() {
+        for facing_position in &FACINGS {
+            assert!(!is_completed_after(
+                Motion::DragonPunch,
+                &[(6, 3), (5, 25), (2, 3), (3, 3)],
+                facing_position
+            ));
+        }
+    }
+
+    #[test]
+    fn require_full_charge() {
+        for facing_position in &FACINGS {
+            assert!(!is_completed_after(
+                Motion::ChargeBackForward,
+                &[(4, 10), (6, 2)],
+                facing_position
+            ));
+        }
+    }
+
+    #[test]
+    fn consume_completed_motion() {
+        let facing_position = FacingPosition::Right;
+        let motion_leniency = MotionLeniency::default();
+        let mut input_buffer = InputBuffer::default();
+        let current_frame = push_directions(
+            &mut input_buffer,
+            0,
+            &[(2, 3), (3, 3), (6, 3)],
+            &facing_position,
+        );
+
+        input_buffer.consume_motions();
+        assert!(!Motion::QuarterCircleForward.is_completed(
+            &input_buffer,
+            &facing_position,
+            &motion_leniency,
+            current_frame,
+        ));
+
+        // still holding forward doesn't make a new one
+        let current_frame = push_directions(
+            &mut input_buffer,
+            current_frame + 1,
+            &[(6, 3)],
+            &facing_position,
+        );
+        assert!(!Motion::QuarterCircleForward.is_completed(
+            &input_buffer,
+            &facing_position,
+            &motion_leniency,
+            current_frame,
+        ));
+    }
+}
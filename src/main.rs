@@ -7,13 +7,21 @@ use bevy_tnua_rapier2d::*;
 
 mod assets;
 mod camera;
+mod character_select;
+mod game_state;
+mod input;
+mod main_menu;
+mod pause;
 mod player;
+mod results;
+mod round;
 mod scenery;
 mod warrior;
 
 const WINDOW_HEIGHT: f32 = 540.0;
 const WINDOW_WIDTH: f32 = 960.0;
 const HALF_WINDOW_HEIGHT: f32 = WINDOW_HEIGHT / 2.;
+const PIXELS_PER_METER: f32 = 100.0;
 // const HALF_WINDOW_WIDTH: f32 = -WINDOW_WIDTH / 2.;
 
 fn main() {
@@ -32,7 +40,7 @@ fn main() {
                 })
                 .set(ImagePlugin::default_nearest()),
             WorldInspectorPlugin::default(),
-            RapierPhysicsPlugin::<()>::pixels_per_meter(100.0),
+            RapierPhysicsPlugin::<()>::pixels_per_meter(PIXELS_PER_METER),
             TnuaControllerPlugin,
             TnuaRapier2dPlugin,
             // TnuaCrouchEnforcerPlugin,
@@ -41,9 +49,18 @@ fn main() {
                 mode: DebugRenderMode::all(),
                 ..default()
             },
+            game_state::GameStatePlugin,
             camera::CameraPlugin,
+            (
+                main_menu::MainMenuPlugin,
+                character_select::CharacterSelectPlugin,
+                results::ResultsPlugin,
+                round::RoundPlugin,
+                pause::PausePlugin,
+            ),
             warrior::WarriorPlugin,
             player::PlayerPlugin,
+            input::PlayerInputPlugin,
             scenery::SceneryPlugin,
             assets::AssetsPlugin,
         ))
@@ -0,0 +1,186 @@
+use bevy::{app::AppExit, ecs::system::EntityCommands, prelude::*};
+
+use crate::game_state::{AppState, DespawnOnExit, PauseState};
+use crate::input::BindingsMenu;
+
+const MENU_BACKGROUND_COLOR: Color = Color::rgb(0.1, 0.1, 0.15);
+const MENU_BUTTON_COLOR: Color = Color::rgb(0.2, 0.2, 0.3);
+const HOVERED_MENU_BUTTON_COLOR: Color = Color::rgb(0.3, 0.3, 0.45);
+const TITLE_COLOR: Color = Color::rgb(1., 0.8, 0.2);
+
+pub struct MainMenuPlugin;
+
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::MainMenu), spawn_main_menu)
+            .add_systems(
+                Update,
+                (
+                    start_versus_with_keys.run_if(in_state(AppState::MainMenu)),
+                    highlight_menu_buttons,
+                    press_menu_buttons,
+                ),
+            );
+    }
+}
+
+/// What pressing the button does, shared by every screen with buttons
+#[derive(Debug, Component, Clone, Copy, PartialEq, Eq)]
+pub enum MenuButton {
+    Versus,
+    Rematch,
+    CharacterSelect,
+    MainMenu,
+    Quit,
+    Resume,
+    MoveList,
+    Controls,
+}
+
+impl MenuButton {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MenuButton::Versus => "Versus",
+            MenuButton::Rematch => "Rematch",
+            MenuButton::CharacterSelect => "Character Select",
+            MenuButton::MainMenu => "Main Menu",
+            MenuButton::Quit => "Quit",
+            MenuButton::Resume => "Resume",
+            MenuButton::MoveList => "Move List",
+            MenuButton::Controls => "Controls",
+        }
+    }
+}
+
+/// Full screen column where the screens place their title and buttons
+pub fn spawn_menu_screen<'w, 's, 'a, S: States>(
+    commands: &'a mut Commands<'w, 's>,
+    name: &'static str,
+    state: S,
+) -> EntityCommands<'w, 's, 'a> {
+    commands.spawn((
+        Name::new(name),
+        DespawnOnExit(state),
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(12.),
+                ..default()
+            },
+            background_color: MENU_BACKGROUND_COLOR.into(),
+            z_index: ZIndex::Global(5),
+            ..default()
+        },
+    ))
+}
+
+pub fn spawn_menu_title(parent: &mut ChildBuilder, title: impl Into<String>) {
+    parent.spawn(TextBundle::from_section(
+        title,
+        TextStyle {
+            font_size: 40.,
+            color: TITLE_COLOR,
+            ..default()
+        },
+    ));
+}
+
+pub fn spawn_menu_button(parent: &mut ChildBuilder, menu_button: MenuButton) {
+    parent
+        .spawn((
+            menu_button,
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(240.),
+                    justify_content: JustifyContent::Center,
+                    padding: UiRect::all(Val::Px(8.)),
+                    ..default()
+                },
+                background_color: MENU_BUTTON_COLOR.into(),
+                ..default()
+            },
+        ))
+        .with_children(|button| {
+            button.spawn(TextBundle::from_section(
+                menu_button.label(),
+                TextStyle {
+                    font_size: 20.,
+                    ..default()
+                },
+            ));
+        });
+}
+
+pub fn spawn_main_menu(mut commands: Commands) {
+    spawn_menu_screen(&mut commands, "Main Menu", AppState::MainMenu).with_children(|menu| {
+        spawn_menu_title(menu, "Jotalovatics War");
+
+        for menu_button in [MenuButton::Versus, MenuButton::Quit] {
+            spawn_menu_button(menu, menu_button);
+        }
+    });
+}
+
+/// Enter or a gamepad's Start also begin a versus
+pub fn start_versus_with_keys(
+    keyboard: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    let start_pressed = keyboard.just_pressed(KeyCode::Return)
+        || gamepad_buttons
+            .get_just_pressed()
+            .any(|button| button.button_type == GamepadButtonType::Start);
+
+    if start_pressed {
+        next_app_state.set(AppState::CharacterSelect);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn highlight_menu_buttons(
+    mut menu_buttons: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<MenuButton>),
+    >,
+) {
+    for (interaction, mut button_color) in &mut menu_buttons {
+        *button_color = match interaction {
+            Interaction::None => MENU_BUTTON_COLOR,
+            _ => HOVERED_MENU_BUTTON_COLOR,
+        }
+        .into();
+    }
+}
+
+pub fn press_menu_buttons(
+    menu_buttons: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+    mut bindings_menu: ResMut<BindingsMenu>,
+    mut app_exit_events: EventWriter<AppExit>,
+) {
+    for (interaction, menu_button) in &menu_buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match menu_button {
+            MenuButton::Versus | MenuButton::CharacterSelect => {
+                next_app_state.set(AppState::CharacterSelect)
+            }
+            MenuButton::Rematch => next_app_state.set(AppState::Fight),
+            MenuButton::MainMenu => next_app_state.set(AppState::MainMenu),
+            MenuButton::Quit => app_exit_events.send(AppExit),
+            MenuButton::Resume => next_pause_state.set(PauseState::Running),
+            MenuButton::Controls => bindings_menu.is_open = true,
+            // the pause menu owns the move list it shows
+            MenuButton::MoveList => {}
+        }
+    }
+}
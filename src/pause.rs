@@ -0,0 +1,171 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::game_state::{AppState, PauseState};
+use crate::input::BindingsMenu;
+use crate::main_menu::{spawn_menu_button, spawn_menu_screen, spawn_menu_title, MenuButton};
+use crate::player::PlayerId;
+use crate::warrior::{MoveList, WarriorKind, WarriorMoves};
+
+pub const PAUSE_KEY: KeyCode = KeyCode::Escape;
+const PAUSE_BACKGROUND_COLOR: Color = Color::rgba(0., 0., 0., 0.7);
+const MOVE_LIST_TEXT_COLOR: Color = Color::rgb(0.8, 0.8, 0.9);
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(PauseState::Paused),
+            (freeze_fight, spawn_pause_menu),
+        )
+        .add_systems(OnExit(PauseState::Paused), resume_fight)
+        .add_systems(OnExit(AppState::Fight), unpause)
+        .add_systems(
+            Update,
+            (toggle_pause, toggle_move_list).run_if(in_state(AppState::Fight)),
+        );
+    }
+}
+
+/// Hidden until the move list button is pressed
+#[derive(Debug, Component)]
+pub struct MoveListPanel;
+
+pub fn toggle_pause(
+    keyboard: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    bindings_menu: Res<BindingsMenu>,
+    pause_state: Res<State<PauseState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    // Esc also cancels a rebind, which comes first
+    if bindings_menu.is_open {
+        return;
+    }
+
+    let pause_pressed = keyboard.just_pressed(PAUSE_KEY)
+        || gamepad_buttons
+            .get_just_pressed()
+            .any(|button| button.button_type == GamepadButtonType::Start);
+
+    if !pause_pressed {
+        return;
+    }
+
+    next_pause_state.set(match pause_state.get() {
+        PauseState::Running => PauseState::Paused,
+        PauseState::Paused => PauseState::Running,
+    });
+}
+
+/// Gameplay systems are already stopped by their set, this also stops physics and time
+pub fn freeze_fight(
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+) {
+    virtual_time.pause();
+    rapier_config.physics_pipeline_active = false;
+}
+
+pub fn resume_fight(
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+) {
+    virtual_time.unpause();
+    rapier_config.physics_pipeline_active = true;
+}
+
+pub fn unpause(mut next_pause_state: ResMut<NextState<PauseState>>) {
+    next_pause_state.set(PauseState::Running);
+}
+
+pub fn spawn_pause_menu(
+    mut commands: Commands,
+    warriors: Query<(&PlayerId, &WarriorKind, &WarriorMoves)>,
+    move_lists: Res<Assets<MoveList>>,
+) {
+    let mut players_moves: Vec<(&PlayerId, &WarriorKind, &WarriorMoves)> =
+        warriors.iter().collect();
+    players_moves.sort_by_key(|(player_id, ..)| **player_id == PlayerId::Two);
+
+    spawn_menu_screen(&mut commands, "Pause Menu", PauseState::Paused)
+        .insert(BackgroundColor(PAUSE_BACKGROUND_COLOR))
+        .with_children(|pause| {
+            spawn_menu_title(pause, "Paused");
+
+            for menu_button in [
+                MenuButton::Resume,
+                MenuButton::MoveList,
+                MenuButton::Controls,
+                MenuButton::MainMenu,
+            ] {
+                spawn_menu_button(pause, menu_button);
+            }
+
+            pause
+                .spawn((
+                    MoveListPanel,
+                    NodeBundle {
+                        style: Style {
+                            display: Display::None,
+                            column_gap: Val::Px(48.),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                ))
+                .with_children(|panel| {
+                    for (player_id, warrior_kind, warrior_moves) in players_moves {
+                        let moves_text = move_lists.get(&warrior_moves.0).map_or(
+                            "loading...".to_string(),
+                            |move_list| {
+                                move_list
+                                    .moves
+                                    .iter()
+                                    .map(|move_definition| {
+                                        let motion = move_definition
+                                            .motion
+                                            .map_or("", |motion| motion.notation());
+                                        format!(
+                                            "{motion} {:?}: {}",
+                                            move_definition.input, move_definition.name
+                                        )
+                                    })
+                                    .collect::<Vec<String>>()
+                                    .join("\n")
+                            },
+                        );
+
+                        panel.spawn(TextBundle::from_section(
+                            format!("{} ({player_id:?})\n{moves_text}", warrior_kind.name()),
+                            TextStyle {
+                                font_size: 16.,
+                                color: MOVE_LIST_TEXT_COLOR,
+                                ..default()
+                            },
+                        ));
+                    }
+                });
+        });
+}
+
+pub fn toggle_move_list(
+    menu_buttons: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    mut move_list_panels: Query<&mut Style, With<MoveListPanel>>,
+) {
+    let move_list_pressed = menu_buttons.iter().any(|(interaction, menu_button)| {
+        *interaction == Interaction::Pressed && *menu_button == MenuButton::MoveList
+    });
+
+    if !move_list_pressed {
+        return;
+    }
+
+    for mut panel_style in &mut move_list_panels {
+        panel_style.display = match panel_style.display {
+            Display::None => Display::Flex,
+            _ => Display::None,
+        };
+    }
+}
@@ -1,7 +1,25 @@
-use crate::warrior::{Speed, WarriorPositionState, WarriorPositionStateTransition};
-use bevy::prelude::*;
-// use bevy_rapier2d::prelude::*;
-use bevy_tnua::prelude::*;
+use crate::warrior::{
+    AttackParty, AttackTriggered, CurrentMove, DamagedState, FacingPosition, FallenTimer, Guard,
+    KnockdownSettings, MoveInput, RiseKind, Speed, Throwing, WarriorHitboxes, WarriorPositionState,
+    WarriorPositionStateTransition,
+};
+use crate::{
+    game_state::FightSet,
+    input::{
+        ActionState, InputBuffer, InputFrameCount, Motion, MotionLeniency, WarriorAction,
+        BUFFERED_INPUT_FRAMES,
+    },
+    round::RoundPhase,
+    WINDOW_WIDTH,
+};
+use bevy::{ecs::query::Has, prelude::*};
+use bevy_rapier2d::prelude::*;
+use bevy_tnua::{prelude::*, TnuaBasis};
+
+/// How fast the walk speed is reached or lost
+pub const WALK_ACCELERATION: f32 = 2000.;
+/// Distance above the feet where the warrior is still held to the floor
+pub const FLOOR_CLING_DISTANCE: f32 = 10.;
 
 pub struct PlayerPlugin;
 
@@ -10,7 +28,10 @@ impl Plugin for PlayerPlugin {
         app.add_systems(
             Update,
             (
-                move_player.in_set(TnuaUserControlsSystemSet),
+                move_player
+                    .in_set(TnuaUserControlsSystemSet)
+                    .in_set(FightSet)
+                    .run_if(in_state(RoundPhase::Fighting)),
                 // process_jump
             ),
         );
@@ -21,22 +42,75 @@ impl Plugin for PlayerPlugin {
 #[reflect(Component)]
 pub struct Player;
 
+/// Slot of a local player, each one with its own side of the stage and bindings
+#[derive(Debug, Component, Clone, Copy, Reflect, Default, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum PlayerId {
+    #[default]
+    One,
+    Two,
+}
+
+impl PlayerId {
+    pub fn attack_party(&self) -> AttackParty {
+        match self {
+            PlayerId::One => AttackParty::Ally,
+            PlayerId::Two => AttackParty::Enemy,
+        }
+    }
+
+    pub fn spawn_x(&self) -> f32 {
+        match self {
+            PlayerId::One => -WINDOW_WIDTH / 3.,
+            PlayerId::Two => WINDOW_WIDTH / 3.,
+        }
+    }
+
+    pub fn opponent(&self) -> PlayerId {
+        match self {
+            PlayerId::One => PlayerId::Two,
+            PlayerId::Two => PlayerId::One,
+        }
+    }
+
+    /// Players start looking at each other
+    pub fn spawn_facing(&self) -> FacingPosition {
+        match self {
+            PlayerId::One => FacingPosition::Right,
+            PlayerId::Two => FacingPosition::Left,
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
 fn move_player(
-    // mut commands: Commands,
-    mut player: Query<
+    mut commands: Commands,
+    mut players: Query<
         (
-            // Entity,
+            Entity,
+            &ActionState,
+            &mut InputBuffer,
+            &FacingPosition,
             &Speed,
+            &WarriorHitboxes,
             &mut WarriorPositionState,
             // &mut KinematicCharacterController,
             // Option<&KinematicCharacterControllerOutput>,
             &mut TnuaController,
             &mut WarriorPositionStateTransition,
+            &DamagedState,
+            Option<&Guard>,
+            Option<&mut FallenTimer>,
+            Has<CurrentMove>,
+            Has<Throwing>,
         ),
         &Player,
     >,
-    keyboard: Res<Input<KeyCode>>,
-    time: Res<Time>,
+    input_frame_count: Res<InputFrameCount>,
+    knockdown_settings: Res<KnockdownSettings>,
+    motion_leniency: Res<MotionLeniency>,
+    rapier_config: Res<RapierConfiguration>,
+    mut attack_triggered_event: EventWriter<AttackTriggered>,
 ) {
     // avaliar se precisa do delta seconds pro movimento
     //! PENDING TESTS
@@ -47,89 +121,236 @@ fn move_player(
     //! should walk when stop crouching and walking
     //! should be jumping when not grounded
 
-    let (
+    for (
         //
-        // entity,
-        player_speed,
+        entity,
+        action_state,
+        mut input_buffer,
+        facing_position,
+        speed,
+        hitboxes,
         mut warrior_position_state,
         mut tnua_controller,
         // mut kinematic_controller,
         // kinematic_output,
         mut position_state_transition,
-    ) = player.single_mut();
-
-    let mut to_move = Vec3::ZERO;
-
-    // if let Some(kinematic_output) = kinematic_output {
-    //     if *warrior_position_state != WarriorPositionState::Idle && kinematic_output.grounded {
-    //         position_state_transition.previous = warrior_position_state.clone();
-    //         *warrior_position_state = WarriorPositionState::Idle;
-    //         commands
-    //             .get_entity(entity)
-    //             .unwrap()
-    //             .remove::<WarriorJumpingTimer>();
-    //     }
-    // }
-
-    if matches!(
-        *warrior_position_state,
-        WarriorPositionState::Idle | WarriorPositionState::Walking
-    ) {
-        if keyboard.pressed(KeyCode::W) {
-            position_state_transition.previous = warrior_position_state.clone();
-            *warrior_position_state = WarriorPositionState::Jumping;
+        damaged_state,
+        guard,
+        fallen_timer,
+        is_performing_move,
+        is_throwing,
+    ) in &mut players
+    {
+        let back_action = match facing_position {
+            FacingPosition::Right => WarriorAction::Left,
+            FacingPosition::Left => WarriorAction::Right,
+        };
+
+        // the guard is kept through blockstun, but not while attacking, airborne or hurt
+        let can_guard = matches!(*damaged_state, DamagedState::None | DamagedState::Blocked)
+            && !is_performing_move
+            && !is_throwing
+            && matches!(
+                *warrior_position_state,
+                WarriorPositionState::Idle
+                    | WarriorPositionState::Walking
+                    | WarriorPositionState::Crouching
+            );
+
+        let new_guard = (can_guard
+            && (action_state.pressed(back_action) || action_state.pressed(WarriorAction::Block)))
+        .then(|| {
+            if action_state.pressed(WarriorAction::Down) {
+                Guard::Crouching
+            } else {
+                Guard::Standing
+            }
+        });
+
+        if guard != new_guard.as_ref() {
+            match new_guard {
+                Some(new_guard) => commands.entity(entity).insert(new_guard),
+                None => commands.entity(entity).remove::<Guard>(),
+            };
         }
 
-        if keyboard.pressed(KeyCode::S) {
+        // the basis is fed every frame, so warriors also stop when they can't walk
+        let can_walk = *damaged_state == DamagedState::None
+            && fallen_timer.is_none()
+            && !is_performing_move
+            && !is_throwing
+            && !matches!(
+                *warrior_position_state,
+                WarriorPositionState::Crouching
+                    | WarriorPositionState::Fallen
+                    | WarriorPositionState::GettingUp
+            );
+        let walk_direction = if can_walk {
+            f32::from(
+                i8::from(action_state.pressed(WarriorAction::Right))
+                    - i8::from(action_state.pressed(WarriorAction::Left)),
+            )
+        } else {
+            0.
+        };
+        tnua_controller.basis(walk_basis(walk_direction, speed, hitboxes));
+
+        // the jump is over once it's done and the warrior is back on the floor
+        if *warrior_position_state == WarriorPositionState::Jumping
+            && tnua_controller.action_name() != Some(TnuaBuiltinJump::NAME)
+            && !is_airborne(&tnua_controller)
+        {
             position_state_transition.previous = warrior_position_state.clone();
-            *warrior_position_state = WarriorPositionState::Crouching;
-            // todo!("Fazer o estado do jogador pra agachar etc");
+            *warrior_position_state = WarriorPositionState::Idle;
+        }
+
+        // control is only recovered when the damage is over (landed, hitstun or stun finished)
+        if *damaged_state != DamagedState::None {
+            continue;
+        }
+
+        if let Some(mut fallen_timer) = fallen_timer {
+            // also picks up the choice made right before hitting the floor
+            let pressed_recently = |action| {
+                input_buffer.pressed_within(action, BUFFERED_INPUT_FRAMES, input_frame_count.0)
+            };
+
+            if pressed_recently(WarriorAction::Down) {
+                fallen_timer.choose_rise(RiseKind::Quick, &knockdown_settings);
+            } else if pressed_recently(WarriorAction::LightAttack) {
+                fallen_timer.choose_rise(RiseKind::Delayed, &knockdown_settings);
+            }
+
+            continue;
+        }
+
+        // committed to the move until its recovery is over
+        if is_performing_move || is_throwing {
+            continue;
         }
-    }
 
-    if keyboard.pressed(KeyCode::A) {
-        if *warrior_position_state == WarriorPositionState::Idle {
+        if matches!(
+            *warrior_position_state,
+            WarriorPositionState::Idle | WarriorPositionState::Walking
+        ) {
+            // holding up after landing doesn't jump again, tnua needs a new press
+            if action_state.just_pressed(WarriorAction::Up) {
+                position_state_transition.previous = warrior_position_state.clone();
+                *warrior_position_state = WarriorPositionState::Jumping;
+            }
+
+            if action_state.pressed(WarriorAction::Down) {
+                position_state_transition.previous = warrior_position_state.clone();
+                *warrior_position_state = WarriorPositionState::Crouching;
+                // todo!("Fazer o estado do jogador pra agachar etc");
+            }
+        }
+
+        if *warrior_position_state == WarriorPositionState::Jumping
+            && action_state.pressed(WarriorAction::Up)
+        {
+            tnua_controller.action(jump_action(speed, rapier_config.gravity.y.abs()));
+        }
+
+        if (action_state.pressed(WarriorAction::Left) || action_state.pressed(WarriorAction::Right))
+            && *warrior_position_state == WarriorPositionState::Idle
+        {
             position_state_transition.previous = warrior_position_state.clone();
             *warrior_position_state = WarriorPositionState::Walking;
         }
 
-        to_move.x -= player_speed.walk * time.delta_seconds();
-    } else if keyboard.pressed(KeyCode::D) {
-        if *warrior_position_state == WarriorPositionState::Idle {
+        // Return to idle
+        if (
+            // Stopped walking
+            *warrior_position_state == WarriorPositionState::Walking
+                && (action_state.just_released(WarriorAction::Left)
+                    || action_state.just_released(WarriorAction::Right))
+        ) || (
+            // Stopped crouching
+            *warrior_position_state == WarriorPositionState::Crouching
+                && action_state.just_released(WarriorAction::Down)
+        ) {
             position_state_transition.previous = warrior_position_state.clone();
-            *warrior_position_state = WarriorPositionState::Walking;
+            *warrior_position_state = WarriorPositionState::Idle
         }
-        to_move.x += player_speed.walk * time.delta_seconds();
-    }
 
-    // Return to idle
-    if (
-        // Stopped walking
-        *warrior_position_state == WarriorPositionState::Walking
-            && (keyboard.just_released(KeyCode::A) || keyboard.just_released(KeyCode::D))
-    ) || (
-        // Stopped crouching
-        *warrior_position_state == WarriorPositionState::Crouching
-            && keyboard.just_released(KeyCode::S)
-    ) {
-        position_state_transition.previous = warrior_position_state.clone();
-        *warrior_position_state = WarriorPositionState::Idle
+        if !matches!(
+            *warrior_position_state,
+            WarriorPositionState::Fallen | WarriorPositionState::GettingUp
+        ) {
+            // presses made a bit early, e.g. during recovery or landing, still come out
+            let mut buffered_press = |action| {
+                input_buffer.consume_pressed_within(
+                    action,
+                    BUFFERED_INPUT_FRAMES,
+                    input_frame_count.0,
+                )
+            };
+
+            let attack_input = if buffered_press(WarriorAction::Throw) {
+                Some(MoveInput::Throw)
+            } else if buffered_press(WarriorAction::LightAttack) {
+                Some(MoveInput::LightAttack)
+            } else if buffered_press(WarriorAction::HeavyAttack) {
+                Some(MoveInput::HeavyAttack)
+            } else {
+                None
+            };
+
+            if let Some(input) = attack_input {
+                let motions = Motion::PRIORITY
+                    .into_iter()
+                    .filter(|motion| {
+                        motion.is_completed(
+                            &input_buffer,
+                            facing_position,
+                            &motion_leniency,
+                            input_frame_count.0,
+                        )
+                    })
+                    .collect();
+
+                attack_triggered_event.send(AttackTriggered {
+                    warrior_entity: entity,
+                    input,
+                    motions,
+                });
+            }
+        }
     }
+}
 
-    // kinematic_controller.translation = Some(to_move);
-    if keyboard.pressed(KeyCode::Space) {
-        to_move += Vec3::new(10., 0., 0.) * 40.;
-        tnua_controller.basis(TnuaBuiltinWalk {
-            desired_velocity: to_move,
-            float_height: 200.0,
-            ..default()
-        });
+/// Keeps the warrior standing on its feet, walking towards the direction at its walk speed
+pub fn walk_basis(
+    walk_direction: f32,
+    speed: &Speed,
+    hitboxes: &WarriorHitboxes,
+) -> TnuaBuiltinWalk {
+    TnuaBuiltinWalk {
+        desired_velocity: Vec3::X * walk_direction * speed.walk,
+        // the body is centered on the standing pushbox, the feet are at its bottom
+        float_height: hitboxes.stood_up_half_extent.1,
+        cling_distance: FLOOR_CLING_DISTANCE,
+        acceleration: WALK_ACCELERATION,
+        // jumps keep the momentum they took off with
+        air_acceleration: 0.,
+        ..default()
     }
-    if keyboard.pressed(KeyCode::H) {
-        to_move += Vec3::new(10., 0., 0.) * 40.;
-        tnua_controller.basis(TnuaBuiltinWalk {
-            float_height: 100.0,
-            ..default()
-        });
+}
+
+/// Off the floor since taking off, or since the walk basis stopped finding it
+pub fn is_airborne(tnua_controller: &TnuaController) -> bool {
+    tnua_controller
+        .concrete_basis::<TnuaBuiltinWalk>()
+        .is_some_and(|(walk, walk_state)| walk.is_airborne(walk_state))
+}
+
+/// Takes off at the jump speed, as high as the gravity lets that speed go
+pub fn jump_action(speed: &Speed, gravity: f32) -> TnuaBuiltinJump {
+    TnuaBuiltinJump {
+        height: speed.jump.powi(2) / (2. * gravity),
+        // letting go of up doesn't cut the jump short
+        shorten_extra_gravity: 0.,
+        ..default()
     }
 }
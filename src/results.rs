@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+
+use crate::character_select::SelectedWarriors;
+use crate::game_state::AppState;
+use crate::main_menu::{spawn_menu_button, spawn_menu_screen, spawn_menu_title, MenuButton};
+use crate::player::PlayerId;
+use crate::round::MatchState;
+
+const SCORE_TEXT_COLOR: Color = Color::rgb(0.8, 0.8, 0.9);
+
+pub struct ResultsPlugin;
+
+impl Plugin for ResultsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FightResult>()
+            .add_systems(OnEnter(AppState::Fight), reset_fight_result)
+            .add_systems(OnEnter(AppState::Results), spawn_results_screen);
+    }
+}
+
+#[derive(Debug, Resource, Default)]
+pub struct FightResult {
+    /// `None` when the match ended even
+    pub winner: Option<PlayerId>,
+}
+
+pub fn reset_fight_result(mut fight_result: ResMut<FightResult>) {
+    *fight_result = FightResult::default();
+}
+
+pub fn spawn_results_screen(
+    mut commands: Commands,
+    fight_result: Res<FightResult>,
+    match_state: Res<MatchState>,
+    selected_warriors: Res<SelectedWarriors>,
+) {
+    let title = fight_result.winner.map_or("Draw!".to_string(), |winner| {
+        format!(
+            "{} ({winner:?}) wins!",
+            selected_warriors.get(&winner).name()
+        )
+    });
+
+    spawn_menu_screen(&mut commands, "Results", AppState::Results).with_children(|results| {
+        spawn_menu_title(results, title);
+
+        results.spawn(TextBundle::from_section(
+            format!(
+                "{} - {}",
+                match_state.wins(&PlayerId::One),
+                match_state.wins(&PlayerId::Two)
+            ),
+            TextStyle {
+                font_size: 28.,
+                color: SCORE_TEXT_COLOR,
+                ..default()
+            },
+        ));
+
+        for menu_button in [
+            MenuButton::Rematch,
+            MenuButton::CharacterSelect,
+            MenuButton::MainMenu,
+        ] {
+            spawn_menu_button(results, menu_button);
+        }
+    });
+}
@@ -0,0 +1,406 @@
+use bevy::prelude::*;
+
+use crate::character_select::SelectedWarriors;
+use crate::game_state::{AppState, DespawnOnExit, FightSet};
+use crate::player::PlayerId;
+use crate::results::FightResult;
+use crate::warrior::{Attack, Health, Warrior, WarriorKnockedOut};
+
+const HUD_TEXT_COLOR: Color = Color::rgb(1., 1., 1.);
+const ANNOUNCER_TEXT_COLOR: Color = Color::rgb(1., 0.8, 0.2);
+/// Part of the intro showing the round number, "Fight!" is shown for the rest
+const ROUND_NUMBER_INTRO_RATIO: f32 = 0.6;
+
+pub struct RoundPlugin;
+
+impl Plugin for RoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<RoundPhase>()
+            .init_resource::<MatchSettings>()
+            .register_type::<MatchSettings>()
+            .init_resource::<MatchState>()
+            .add_systems(OnEnter(AppState::Fight), (start_match, spawn_round_hud))
+            .add_systems(OnExit(AppState::Fight), stop_rounds)
+            .add_systems(OnEnter(RoundPhase::Intro), start_round)
+            .add_systems(
+                Update,
+                (
+                    finish_round_intro.run_if(in_state(RoundPhase::Intro)),
+                    count_down_round.run_if(in_state(RoundPhase::Fighting)),
+                    // a knockout as time runs out is still a knockout
+                    knock_out_round
+                        .before(count_down_round)
+                        .run_if(in_state(RoundPhase::Fighting))
+                        .run_if(on_event::<WarriorKnockedOut>()),
+                    finish_round_outro.run_if(in_state(RoundPhase::Outro)),
+                    update_round_hud,
+                )
+                    .in_set(FightSet),
+            );
+    }
+}
+
+/// Steps of each round, players only control their warriors while fighting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, States)]
+pub enum RoundPhase {
+    /// Outside of fights
+    #[default]
+    Inactive,
+    /// "Round 1 / Fight!", with the warriors back at their spawn
+    Intro,
+    Fighting,
+    /// The round was decided, the winner is announced before the next one
+    Outro,
+}
+
+#[derive(Debug, Resource, Reflect)]
+#[reflect(Resource)]
+pub struct MatchSettings {
+    /// 2 makes it a best of 3
+    pub rounds_to_win: u32,
+    pub round_seconds: f32,
+    pub intro_seconds: f32,
+    pub outro_seconds: f32,
+}
+
+impl Default for MatchSettings {
+    fn default() -> Self {
+        MatchSettings {
+            rounds_to_win: 2,
+            round_seconds: 99.,
+            intro_seconds: 2.,
+            outro_seconds: 3.,
+        }
+    }
+}
+
+impl MatchSettings {
+    /// Draws don't award anyone, so the match still ends after this many rounds
+    pub fn max_rounds(&self) -> u32 {
+        self.rounds_to_win * 2 - 1
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundEnd {
+    Knockout,
+    DoubleKnockout,
+    /// Won by the remaining health percentage
+    TimeUp,
+}
+
+#[derive(Debug, Resource, Default)]
+pub struct MatchState {
+    /// Starts at 1 with the first intro
+    pub round: u32,
+    pub player_one_wins: u32,
+    pub player_two_wins: u32,
+    /// Counts down the time left to fight
+    pub round_timer: Timer,
+    /// Length of the current intro or outro
+    pub phase_timer: Timer,
+    pub round_end: Option<RoundEnd>,
+    /// `None` after a draw
+    pub round_winner: Option<PlayerId>,
+}
+
+impl MatchState {
+    pub fn wins(&self, player_id: &PlayerId) -> u32 {
+        match player_id {
+            PlayerId::One => self.player_one_wins,
+            PlayerId::Two => self.player_two_wins,
+        }
+    }
+
+    /// Player with more rounds won, `None` when even
+    pub fn leader(&self) -> Option<PlayerId> {
+        match self.player_one_wins.cmp(&self.player_two_wins) {
+            std::cmp::Ordering::Greater => Some(PlayerId::One),
+            std::cmp::Ordering::Less => Some(PlayerId::Two),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// Only the first end of a round counts, the phase changes a frame later
+    fn end_round(
+        &mut self,
+        round_end: RoundEnd,
+        round_winner: Option<PlayerId>,
+        outro_seconds: f32,
+    ) {
+        if self.round_end.is_some() {
+            return;
+        }
+
+        match round_winner {
+            Some(PlayerId::One) => self.player_one_wins += 1,
+            Some(PlayerId::Two) => self.player_two_wins += 1,
+            None => {}
+        }
+
+        self.round_end = Some(round_end);
+        self.round_winner = round_winner;
+        self.phase_timer = Timer::from_seconds(outro_seconds, TimerMode::Once);
+    }
+}
+
+#[derive(Debug, Component)]
+pub struct RoundTimerText;
+
+#[derive(Debug, Component)]
+pub struct RoundWinsText(pub PlayerId);
+
+#[derive(Debug, Component)]
+pub struct AnnouncerText;
+
+pub fn start_match(
+    mut match_state: ResMut<MatchState>,
+    mut next_round_phase: ResMut<NextState<RoundPhase>>,
+) {
+    *match_state = MatchState::default();
+    next_round_phase.set(RoundPhase::Intro);
+}
+
+pub fn stop_rounds(mut next_round_phase: ResMut<NextState<RoundPhase>>) {
+    next_round_phase.set(RoundPhase::Inactive);
+}
+
+/// Warriors of the last round are despawned, they are spawned again good as new
+#[allow(clippy::type_complexity)]
+pub fn start_round(
+    mut commands: Commands,
+    match_settings: Res<MatchSettings>,
+    mut match_state: ResMut<MatchState>,
+    round_entities: Query<Entity, Or<(With<Warrior>, With<Attack>)>>,
+) {
+    for round_entity in &round_entities {
+        commands.entity(round_entity).despawn_recursive();
+    }
+
+    match_state.round += 1;
+    match_state.round_end = None;
+    match_state.round_winner = None;
+    match_state.round_timer = Timer::from_seconds(match_settings.round_seconds, TimerMode::Once);
+    match_state.phase_timer = Timer::from_seconds(match_settings.intro_seconds, TimerMode::Once);
+}
+
+pub fn finish_round_intro(
+    time: Res<Time>,
+    mut match_state: ResMut<MatchState>,
+    mut next_round_phase: ResMut<NextState<RoundPhase>>,
+) {
+    if match_state.phase_timer.tick(time.delta()).just_finished() {
+        next_round_phase.set(RoundPhase::Fighting);
+    }
+}
+
+pub fn count_down_round(
+    time: Res<Time>,
+    match_settings: Res<MatchSettings>,
+    mut match_state: ResMut<MatchState>,
+    players: Query<(&PlayerId, &Health)>,
+    mut next_round_phase: ResMut<NextState<RoundPhase>>,
+) {
+    if !match_state.round_timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let health_ratio = |player_id: PlayerId| {
+        players
+            .iter()
+            .find(|(warrior_player_id, _)| **warrior_player_id == player_id)
+            .map_or(0., |(_, health)| health.current / health.max)
+    };
+
+    let player_one_health = health_ratio(PlayerId::One);
+    let player_two_health = health_ratio(PlayerId::Two);
+
+    let round_winner = if player_one_health > player_two_health {
+        Some(PlayerId::One)
+    } else if player_two_health > player_one_health {
+        Some(PlayerId::Two)
+    } else {
+        None
+    };
+
+    match_state.end_round(RoundEnd::TimeUp, round_winner, match_settings.outro_seconds);
+    next_round_phase.set(RoundPhase::Outro);
+}
+
+pub fn knock_out_round(
+    mut knocked_out_events: EventReader<WarriorKnockedOut>,
+    match_settings: Res<MatchSettings>,
+    mut match_state: ResMut<MatchState>,
+    players: Query<&PlayerId>,
+    mut next_round_phase: ResMut<NextState<RoundPhase>>,
+) {
+    let knocked_out_players: Vec<PlayerId> = knocked_out_events
+        .read()
+        .filter_map(|knocked_out_event| players.get(knocked_out_event.warrior_entity).ok())
+        .copied()
+        .collect();
+
+    let (round_end, round_winner) = match knocked_out_players.as_slice() {
+        [] => return,
+        [knocked_out_player] => (RoundEnd::Knockout, Some(knocked_out_player.opponent())),
+        _ => (RoundEnd::DoubleKnockout, None),
+    };
+
+    match_state.end_round(round_end, round_winner, match_settings.outro_seconds);
+    next_round_phase.set(RoundPhase::Outro);
+}
+
+pub fn finish_round_outro(
+    time: Res<Time>,
+    match_settings: Res<MatchSettings>,
+    mut match_state: ResMut<MatchState>,
+    mut fight_result: ResMut<FightResult>,
+    mut next_round_phase: ResMut<NextState<RoundPhase>>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if !match_state.phase_timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let has_match_winner = [PlayerId::One, PlayerId::Two]
+        .iter()
+        .any(|player_id| match_state.wins(player_id) >= match_settings.rounds_to_win);
+
+    if !has_match_winner && match_state.round < match_settings.max_rounds() {
+        next_round_phase.set(RoundPhase::Intro);
+        return;
+    }
+
+    fight_result.winner = match_state.leader();
+    next_app_state.set(AppState::Results);
+}
+
+pub fn spawn_round_hud(mut commands: Commands) {
+    let hud_text_style = TextStyle {
+        font_size: 24.,
+        color: HUD_TEXT_COLOR,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Name::new("Round HUD"),
+            DespawnOnExit(AppState::Fight),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::all(Val::Px(12.)),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|hud| {
+            hud.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    justify_content: JustifyContent::SpaceBetween,
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|top_bar| {
+                top_bar.spawn((
+                    RoundWinsText(PlayerId::One),
+                    TextBundle::from_section("", hud_text_style.clone()),
+                ));
+                top_bar.spawn((
+                    RoundTimerText,
+                    TextBundle::from_section("", hud_text_style.clone()),
+                ));
+                top_bar.spawn((
+                    RoundWinsText(PlayerId::Two),
+                    TextBundle::from_section("", hud_text_style.clone()),
+                ));
+            });
+
+            hud.spawn((
+                AnnouncerText,
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 48.,
+                        color: ANNOUNCER_TEXT_COLOR,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::top(Val::Percent(15.)),
+                    ..default()
+                }),
+            ));
+        });
+}
+
+#[allow(clippy::type_complexity)]
+pub fn update_round_hud(
+    match_settings: Res<MatchSettings>,
+    match_state: Res<MatchState>,
+    round_phase: Res<State<RoundPhase>>,
+    selected_warriors: Res<SelectedWarriors>,
+    mut timer_texts: Query<&mut Text, With<RoundTimerText>>,
+    mut wins_texts: Query<(&RoundWinsText, &mut Text), Without<RoundTimerText>>,
+    mut announcer_texts: Query<
+        &mut Text,
+        (
+            With<AnnouncerText>,
+            Without<RoundTimerText>,
+            Without<RoundWinsText>,
+        ),
+    >,
+) {
+    for mut timer_text in &mut timer_texts {
+        timer_text.sections[0].value =
+            format!("{:.0}", match_state.round_timer.remaining_secs().ceil());
+    }
+
+    for (wins_text, mut text) in &mut wins_texts {
+        let player_id = wins_text.0;
+        let wins = match_state.wins(&player_id);
+        let marks: String = (0..match_settings.rounds_to_win)
+            .map(|round| if round < wins { '*' } else { '-' })
+            .collect();
+        let name = selected_warriors.get(&player_id).name();
+
+        text.sections[0].value = match player_id {
+            PlayerId::One => format!("{name} {marks}"),
+            PlayerId::Two => format!("{marks} {name}"),
+        };
+    }
+
+    let announcement = match round_phase.get() {
+        RoundPhase::Intro if match_state.phase_timer.percent() < ROUND_NUMBER_INTRO_RATIO => {
+            format!("Round {}", match_state.round)
+        }
+        RoundPhase::Intro => "Fight!".to_string(),
+        RoundPhase::Outro => {
+            let round_end = match match_state.round_end {
+                Some(RoundEnd::Knockout) => "K.O.",
+                Some(RoundEnd::DoubleKnockout) => "Double K.O.",
+                Some(RoundEnd::TimeUp) => "Time!",
+                None => "",
+            };
+            let round_result = match_state
+                .round_winner
+                .map_or("Draw".to_string(), |winner| {
+                    format!("{} wins the round", selected_warriors.get(&winner).name())
+                });
+
+            format!("{round_end}\n{round_result}")
+        }
+        RoundPhase::Fighting | RoundPhase::Inactive => String::new(),
+    };
+
+    for mut announcer_text in &mut announcer_texts {
+        announcer_text.sections[0].value = announcement.clone();
+    }
+}
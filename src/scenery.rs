@@ -1,6 +1,7 @@
-use crate::{HALF_WINDOW_HEIGHT, WINDOW_HEIGHT, WINDOW_WIDTH};
+use crate::game_state::{AppState, DespawnOnExit};
+use crate::warrior::{PUSHBOX_GROUP, SCENERY_GROUP};
+use crate::{HALF_WINDOW_HEIGHT, PIXELS_PER_METER, WINDOW_HEIGHT, WINDOW_WIDTH};
 use bevy::prelude::*;
-use bevy_asset_loader::asset_collection::AssetCollectionApp;
 use bevy_asset_loader::prelude::*;
 use bevy_rapier2d::prelude::*;
 
@@ -9,12 +10,9 @@ pub struct SceneryPlugin;
 
 impl Plugin for SceneryPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.init_collection::<SceneryAssets>()
-            .add_systems(Startup, (spawn_floor, apply_gravity))
-            .add_systems(
-                Update,
-                spawn_background.run_if(on_event::<AssetEvent<Image>>()),
-            );
+        app.add_collection_to_loading_state::<_, SceneryAssets>(AppState::Loading)
+            .add_systems(Startup, apply_gravity)
+            .add_systems(OnEnter(AppState::Fight), (spawn_floor, spawn_background));
     }
 }
 
@@ -29,45 +27,37 @@ pub struct SceneryAssets {
 pub struct Floor;
 
 fn spawn_background(
-    mut asset_events: EventReader<AssetEvent<Image>>,
     mut commands: Commands,
     scenery_assets: Res<SceneryAssets>,
     assets: Res<Assets<Image>>,
 ) {
-    for event in asset_events.read() {
-        match event {
-            AssetEvent::Added { id } => {
-                // a texture was just loaded or changed!
-                if *id != scenery_assets.cicest_sprite.id() {
-                    continue;
-                }
+    let Some(scenery_image) = assets.get(&scenery_assets.cicest_sprite) else {
+        return;
+    };
 
-                let scenery_size = assets.get(*id).unwrap().size().as_vec2();
-                let scale_proportion = WINDOW_HEIGHT / scenery_size.y;
+    let scenery_size = scenery_image.size().as_vec2();
+    let scale_proportion = WINDOW_HEIGHT / scenery_size.y;
 
-                commands.spawn((
-                    Name::new("Background"),
-                    SpriteBundle {
-                        sprite: Sprite {
-                            custom_size: Some(scenery_size * scale_proportion),
-                            ..default()
-                        },
-                        texture: scenery_assets.cicest_sprite.clone(),
-                        // transform: Transform::from_scale(Vec3::new(1.65, 1.65, 1.)),
-                        ..default()
-                    },
-                ));
-                break;
-            }
-            _ => {}
-        }
-    }
+    commands.spawn((
+        Name::new("Background"),
+        DespawnOnExit(AppState::Fight),
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(scenery_size * scale_proportion),
+                ..default()
+            },
+            texture: scenery_assets.cicest_sprite.clone(),
+            // transform: Transform::from_scale(Vec3::new(1.65, 1.65, 1.)),
+            ..default()
+        },
+    ));
 }
 
 fn spawn_floor(mut commands: Commands) {
     commands.spawn((
         Name::new("Floor"),
         Floor,
+        DespawnOnExit(AppState::Fight),
         SpriteBundle {
             sprite: Sprite {
                 custom_size: Some(Vec2::new(WINDOW_WIDTH, FLOOR_HEIGHT)),
@@ -79,9 +69,11 @@ fn spawn_floor(mut commands: Commands) {
         },
         // Collider::cuboid(WINDOW_WIDTH / 2., FLOOR_HEIGHT / 2.),
         Collider::halfspace(Vec2::Y).unwrap(),
+        CollisionGroups::new(SCENERY_GROUP, PUSHBOX_GROUP),
     ));
 }
 
 fn apply_gravity(mut rapier_config: ResMut<RapierConfiguration>) {
-    rapier_config.gravity = Vec2::Y * -9.81;
+    // the gravity is in pixels, like everything else outside rapier
+    rapier_config.gravity = Vec2::Y * -9.81 * PIXELS_PER_METER;
 }
@@ -1,7 +1,11 @@
 use crate::assets::{WarriorAssets, WARRIOR_IN_GAME_SPRITE_SIZE};
-use crate::player::Player;
+use crate::character_select::SelectedWarriors;
+use crate::game_state::{AppState, DespawnOnExit, FightSet};
+use crate::input::{ActionState, InputBuffer};
+use crate::player::{Player, PlayerId};
+use crate::round::RoundPhase;
 use crate::scenery::FLOOR_HEIGHT;
-use crate::{HALF_WINDOW_HEIGHT, WINDOW_WIDTH};
+use crate::HALF_WINDOW_HEIGHT;
 use bevy::ecs::system::EntityCommands;
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
@@ -10,12 +14,24 @@ use bevy_tnua::controller::TnuaControllerBundle;
 use bevy_tnua_rapier2d::TnuaRapier2dIOBundle;
 
 mod attack;
+mod character;
+mod combo;
+mod facing;
+mod guard;
 mod hitbox;
+mod hitstop;
+mod knockback;
+mod knockdown;
+mod moves;
 mod sprites;
 mod states;
 mod stats;
+mod throw;
 
-pub use self::{attack::*, hitbox::*, sprites::*, states::*, stats::*};
+pub use self::{
+    attack::*, character::*, combo::*, facing::*, guard::*, hitbox::*, hitstop::*, knockback::*,
+    knockdown::*, moves::*, sprites::*, states::*, stats::*, throw::*,
+};
 
 pub struct WarriorPlugin;
 
@@ -23,30 +39,106 @@ impl Plugin for WarriorPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<UpdateWarriorHitbox>()
             .add_event::<AttackHit>()
+            .add_event::<AttackTriggered>()
+            .add_event::<WarriorKnockedOut>()
+            .add_event::<WarriorKnockedDown>()
+            .add_event::<ThrowAttempted>()
+            .add_event::<ComboHit>()
+            .add_event::<ComboStarted>()
+            .add_event::<ComboEnded>()
+            .init_resource::<KnockdownSettings>()
+            .register_type::<KnockdownSettings>()
             .register_type::<DamagedState>()
             .register_type::<WarriorPositionState>()
             .register_type::<WarriorPositionStateTransition>()
             .register_type::<FacingPosition>()
             .register_type::<WarriorKind>()
+            .register_type::<PlayerId>()
             .register_type::<Speed>()
+            .register_type::<Health>()
+            .register_type::<StunMeter>()
+            .register_type::<Weight>()
+            .register_type::<StunnedTimer>()
+            .register_type::<Guard>()
+            .register_type::<ComboState>()
+            .register_type::<Hitstop>()
+            .register_type::<Launched>()
+            .register_type::<Throwing>()
+            .register_type::<Thrown>()
+            .register_type::<ThrowTimer>()
+            .register_type::<Invulnerable>()
+            .register_type::<Pushbox>()
+            .register_type::<Hurtbox>()
+            .register_type::<FallenTimer>()
+            .register_type::<GettingUpTimer>()
             .register_type::<SpriteAnimationTimer>()
             .register_type::<Attack>()
             .register_type::<AttackParty>()
             .register_type::<DamagedTimer>()
-            .add_systems(Startup, (spawn_warrior, tmp_spawn_fixed_attack))
+            .register_type::<AttackTimer>()
+            .register_type::<AttackHistory>()
+            .register_type::<AttackOwner>()
+            .register_type::<WarriorMoves>()
+            .register_type::<CurrentMove>()
+            .register_type::<WarriorCharacter>()
+            .register_type::<WarriorHitboxes>()
+            .add_systems(OnEnter(RoundPhase::Intro), spawn_warrior)
             .add_systems(
                 Update,
                 (
-                    update_warriors_sprites,
+                    apply_characters_data,
+                    update_warriors_sprites.after(apply_characters_data),
+                    face_opponents.before(update_warriors_hitbox),
+                    flip_warriors_sprites.after(face_opponents),
                     update_warriors_hitbox
                         .run_if(on_event::<UpdateWarriorHitbox>())
                         .after(update_warriors_sprites),
+                    start_warriors_moves.run_if(on_event::<AttackTriggered>()),
+                    advance_warriors_moves.after(start_warriors_moves),
+                    follow_attack_owners.after(advance_warriors_moves),
+                    attack_timer_finished.run_if(on_event::<StateTimerFinished<AttackTimer>>()),
                     attack_collides_player,
                     attack_hits
                         .run_if(on_event::<AttackHit>())
                         .after(attack_collides_player),
+                    knock_out_warriors
+                        .run_if(on_event::<WarriorKnockedOut>())
+                        .after(attack_hits),
                     damage_timer_finished.run_if(on_event::<StateTimerFinished<DamagedTimer>>()),
-                ),
+                    stunned_timer_finished.run_if(on_event::<StateTimerFinished<StunnedTimer>>()),
+                    decay_stun_meters,
+                    land_launched_warriors.after(attack_hits),
+                    knock_down_warriors
+                        .run_if(on_event::<WarriorKnockedDown>())
+                        .after(land_launched_warriors),
+                    fallen_timer_finished.run_if(on_event::<StateTimerFinished<FallenTimer>>()),
+                    getting_up_timer_finished
+                        .run_if(on_event::<StateTimerFinished<GettingUpTimer>>()),
+                )
+                    .in_set(FightSet),
+            )
+            .add_systems(
+                Update,
+                (
+                    grab_warriors
+                        .run_if(on_event::<ThrowAttempted>())
+                        .after(advance_warriors_moves),
+                    hold_thrown_warriors.after(grab_warriors),
+                    tech_throws.after(grab_warriors),
+                    throw_timer_finished
+                        .run_if(on_event::<StateTimerFinished<ThrowTimer>>())
+                        .after(tech_throws),
+                    end_hitstops.before(attack_hits),
+                    count_combo_hits
+                        .run_if(on_event::<ComboHit>())
+                        .after(attack_hits),
+                    end_combos
+                        .after(count_combo_hits)
+                        .after(damage_timer_finished)
+                        .after(land_launched_warriors),
+                    log_combos.after(end_combos),
+                )
+                    .in_set(FightSet),
             )
             .register_state_timer_systems();
     }
@@ -58,30 +150,50 @@ pub struct Warrior;
 
 pub fn spawn_warrior_bundle<'w, 's, 'a>(
     commands: &'a mut Commands<'w, 's>,
-    entity_name: Option<&'static str>,
+    entity_name: Option<String>,
     selected_warrior: WarriorKind,
-    warrior_assets: Res<WarriorAssets>,
-    player: Option<Player>,
+    warrior_assets: &WarriorAssets,
+    player_id: Option<PlayerId>,
 ) -> EntityCommands<'w, 's, 'a> {
     let default_position_state = WarriorPositionState::default();
-    let hitbox = default_position_state.get_position_hitbox();
+    let character_data = CharacterData::default();
+    let roster_entry = warrior_assets.roster_entry(&selected_warrior);
+    let facing_position = player_id.map_or_else(FacingPosition::default, |player_id| {
+        player_id.spawn_facing()
+    });
 
     let mut warrior_commands = commands.spawn((
-        Warrior,
+        (Warrior, DespawnOnExit(AppState::Fight)),
         selected_warrior,
-        default_position_state,
+        default_position_state.clone(),
         WarriorPositionStateTransition::default(),
-        Speed {
-            walk: 180.,
-            jump: 450.,
-        },
-        DamagedState::None,
-        FacingPosition::default(),
+        (
+            character_data.speed,
+            Health::new(character_data.health),
+            StunMeter::new(
+                character_data.stun_meter.max,
+                character_data.stun_meter.decay,
+            ),
+            Weight(character_data.weight),
+        ),
+        (
+            WarriorMoves(roster_entry.moves),
+            WarriorCharacter(roster_entry.character),
+            character_data.hitboxes.clone(),
+        ),
+        (
+            DamagedState::None,
+            facing_position.clone(),
+            player_id.map_or(AttackParty::Enemy, |player_id| player_id.attack_party()),
+            ComboState::default(),
+        ),
         SpriteAnimationTimer {
-            timer: Timer::from_seconds(0.75, TimerMode::Repeating),
+            timer: Timer::from_seconds(
+                character_data.sprite_animation_seconds,
+                TimerMode::Repeating,
+            ),
         },
         RigidBody::Dynamic,
-        Collider::cuboid(hitbox.0, hitbox.1),
         // KinematicCharacterController::default(),
         // CollisionGroups::new(Group::NONE, Group::NONE),
         // SolverGroups::new(Group::NONE, Group::NONE),
@@ -96,11 +208,11 @@ pub fn spawn_warrior_bundle<'w, 's, 'a>(
                 ..default()
             },
             transform: Transform::from_xyz(
-                -WINDOW_WIDTH / 3.,
+                player_id.map_or(0., |player_id| player_id.spawn_x()),
                 -HALF_WINDOW_HEIGHT + FLOOR_HEIGHT + WARRIOR_IN_GAME_SPRITE_SIZE.y / 2. + 40.,
                 0.,
             ),
-            texture_atlas: warrior_assets.jotaile_sprites.clone(),
+            texture_atlas: roster_entry.sprites,
             ..default()
         },
         TnuaControllerBundle::default(),
@@ -109,60 +221,60 @@ pub fn spawn_warrior_bundle<'w, 's, 'a>(
         // TnuaRapier2dSensorShape(Collider::cuboid(hitbox.0, hitbox.1)),
     ));
 
-    // warrior_commands.with_children(|parent| {
-    //     parent.spawn((
-    //         Sprite,
-    //         SpriteSheetBundle {
-    //             sprite: TextureAtlasSprite {
-    //                 index: 0,
-    //                 custom_size: Some(WARRIOR_IN_GAME_SPRITE_SIZE),
-    //                 anchor: Anchor::BottomCenter,
-    //                 ..default()
-    //             },
-    //             transform: Transform::from_xyz(0., -WARRIOR_IN_GAME_SPRITE_SIZE.y / 2., 0.),
-    //             texture_atlas: warrior_assets.jotaile_sprites.clone(),
-    //             ..default()
-    //         },
-    //     ));
-
-    // parent.spawn((
-    //     Name::new("Hitbox"),
-    //     Hitbox,
-    //     Collider::cuboid(hitbox.0, hitbox.1),
-    //     Sensor,
-    //     TransformBundle::from_transform(Transform::from_xyz(-1., 0., 0.)),
-    //     CollisionGroups::new(Group::NONE, Group::NONE),
-    // ));
+    warrior_commands.with_children(|parent| {
+        // parent.spawn((
+        //     Sprite,
+        //     SpriteSheetBundle {
+        //         sprite: TextureAtlasSprite {
+        //             index: 0,
+        //             custom_size: Some(WARRIOR_IN_GAME_SPRITE_SIZE),
+        //             anchor: Anchor::BottomCenter,
+        //             ..default()
+        //         },
+        //         transform: Transform::from_xyz(0., -WARRIOR_IN_GAME_SPRITE_SIZE.y / 2., 0.),
+        //         texture_atlas: warrior_assets.jotaile_sprites.clone(),
+        //         ..default()
+        //     },
+        // ));
 
-    // let crouch_hitbox = WarriorPositionState::Crouching.get_position_hitbox();
+        spawn_hitbox_children(
+            parent,
+            &default_position_state,
+            &facing_position,
+            &character_data.hitboxes,
+        );
+    });
 
-    // parent.spawn((
-    //     Name::new("CrouchHitbox"),
-    //     CrouchHitbox,
-    //     Collider::cuboid(crouch_hitbox.0, crouch_hitbox.1),
-    //     Sensor,
-    //     TransformBundle::from_transform(Transform::from_xyz(0., -crouch_hitbox.1, 0.)),
-    //     // CollisionGroups::new(Group::GROUP_2, Group::GROUP_1),
-    // ));
-    // });
-
-    if player.is_some() {
-        warrior_commands.insert(Player);
+    if let Some(player_id) = player_id {
+        warrior_commands.insert((
+            Player,
+            player_id,
+            ActionState::default(),
+            InputBuffer::default(),
+        ));
     }
 
-    warrior_commands.insert(Name::new(entity_name.unwrap_or("Warrior")));
+    warrior_commands.insert(Name::new(
+        entity_name.unwrap_or_else(|| selected_warrior.name().to_string()),
+    ));
 
     warrior_commands
 }
 
-pub fn spawn_warrior(mut commands: Commands, warrior_assets: Res<WarriorAssets>) {
-    let selected_warrior = WarriorKind::Jotaile;
+pub fn spawn_warrior(
+    mut commands: Commands,
+    warrior_assets: Res<WarriorAssets>,
+    selected_warriors: Res<SelectedWarriors>,
+) {
+    for player_id in [PlayerId::One, PlayerId::Two] {
+        let warrior_kind = selected_warriors.get(&player_id);
 
-    spawn_warrior_bundle(
-        &mut commands,
-        Some("Jotaile"),
-        selected_warrior,
-        warrior_assets,
-        Some(Player),
-    );
+        spawn_warrior_bundle(
+            &mut commands,
+            Some(format!("{} ({player_id:?})", warrior_kind.name())),
+            warrior_kind,
+            &warrior_assets,
+            Some(player_id),
+        );
+    }
 }
@@ -1,13 +1,76 @@
-use bevy::{ecs::query::Has, prelude::*};
+use bevy::{prelude::*, utils::HashMap};
 use bevy_rapier2d::prelude::*;
+use bevy_tnua::{prelude::*, TnuaToggle};
 
-use crate::player::Player;
+use crate::input::Motion;
+use crate::player::is_airborne;
 
-use super::{DamagedState, DamagedTimer, StateTimerFinished, Warrior};
+use super::{
+    continues_combo, set_linvel, start_hitstop, AttackHeight, ComboHit, CurrentMove, DamagedState,
+    DamagedTimer, Guard, Health, Hitstop, Hurtbox, Invulnerable, Launched, MoveInput, StateTimer,
+    StateTimerFinished, StunMeter, StunnedTimer, Warrior, WarriorPositionState,
+    WarriorPositionStateTransition, Weight,
+};
 
+pub const FRAME_DURATION: f32 = 1. / 60.;
+pub const STUNNED_SECONDS: f32 = 2.;
+/// Part of the knockback that still pushes a blocking warrior back
+pub const BLOCK_PUSHBACK_RATIO: f32 = 0.5;
+
+#[derive(Debug, Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Attack {
+    pub damage: f32,
+    pub stun: f32,
+    pub knockback: Vec2,
+    pub launcher: bool,
+    pub hitstun: f32,
+    pub blockstun: f32,
+    pub hitstop: f32,
+    pub height: AttackHeight,
+    pub chip_damage: f32,
+    /// Seconds until the same warrior can be hit again, hits only once when `None`
+    pub multi_hit_interval: Option<f32>,
+}
+
+/// Warriors already struck by the attack, with the elapsed seconds of their last hit
 #[derive(Debug, Component, Reflect, Default)]
 #[reflect(Component)]
-pub struct Attack;
+pub struct AttackHistory {
+    pub last_hits: HashMap<Entity, f32>,
+}
+
+impl AttackHistory {
+    /// Registers the hit if the warrior can be hit by this attack now
+    pub fn try_hit(&mut self, warrior_entity: Entity, attack: &Attack, elapsed: f32) -> bool {
+        if let Some(last_hit) = self.last_hits.get(&warrior_entity) {
+            match attack.multi_hit_interval {
+                Some(interval) if elapsed - last_hit >= interval => {}
+                _ => return false,
+            }
+        }
+
+        self.last_hits.insert(warrior_entity, elapsed);
+        true
+    }
+}
+
+#[derive(Debug, Component, Reflect)]
+#[reflect(Component)]
+pub struct AttackOwner {
+    pub warrior_entity: Entity,
+    /// Already mirrored by the owner's [`FacingPosition`](super::FacingPosition)
+    pub offset: Vec2,
+}
+
+impl Default for AttackOwner {
+    fn default() -> Self {
+        AttackOwner {
+            warrior_entity: Entity::PLACEHOLDER,
+            offset: Vec2::ZERO,
+        }
+    }
+}
 
 #[derive(Debug, Component, Clone, Default, Reflect, PartialEq, Eq)]
 #[reflect(Component)]
@@ -17,90 +80,362 @@ pub enum AttackParty {
     Enemy,
 }
 
+/// Despawns the attack when its active frames are over
+#[derive(Debug, Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct AttackTimer {
+    pub timer: Timer,
+}
+
+impl StateTimer for AttackTimer {
+    fn get_timer(&mut self) -> &mut Timer {
+        &mut self.timer
+    }
+}
+
 #[derive(Event)]
 pub struct AttackHit {
     pub warrior_entity: Entity,
     pub attack_entity: Entity,
 }
 
-pub fn tmp_spawn_fixed_attack(mut commands: Commands) {
-    commands.spawn((
-        Name::new("attack"),
-        Attack,
-        AttackParty::Enemy,
-        Transform::from_xyz(0., 0., 0.),
-        Collider::cuboid(5., 5.),
-    ));
+#[derive(Event)]
+pub struct WarriorKnockedOut {
+    pub warrior_entity: Entity,
+}
+
+#[derive(Event)]
+pub struct AttackTriggered {
+    pub warrior_entity: Entity,
+    pub input: MoveInput,
+    /// Motions completed right before the input, highest priority first
+    pub motions: Vec<Motion>,
+}
+
+/// Keeps the attack in front of the warrior performing it
+pub fn follow_attack_owners(
+    mut attacks: Query<(&AttackOwner, &mut Transform), &Attack>,
+    warriors: Query<&Transform, (&Warrior, Without<Attack>)>,
+) {
+    for (attack_owner, mut attack_transform) in &mut attacks {
+        let Ok(warrior_transform) = warriors.get(attack_owner.warrior_entity) else {
+            continue;
+        };
+
+        attack_transform.translation =
+            warrior_transform.translation + attack_owner.offset.extend(0.);
+    }
+}
+
+pub fn attack_timer_finished(
+    mut commands: Commands,
+    mut attack_timer_finished_events: EventReader<StateTimerFinished<AttackTimer>>,
+) {
+    for attack_timer_finished_event in attack_timer_finished_events.read() {
+        if let Some(attack_commands) = commands.get_entity(attack_timer_finished_event.0) {
+            attack_commands.despawn_recursive();
+        }
+    }
 }
 
 pub fn attack_collides_player(
-    warriors: Query<(Entity, &KinematicCharacterControllerOutput, Has<Player>), &Warrior>,
-    attacks: Query<(Entity, &AttackParty), &Attack>,
-    // query: Query<&Name>,
+    mut attacks: Query<(Entity, &Attack, &AttackParty, &mut AttackHistory)>,
+    hurtboxes: Query<&Parent, &Hurtbox>,
+    warriors: Query<(&AttackParty, &Health, Has<Invulnerable>), &Warrior>,
+    rapier_context: Res<RapierContext>,
+    time: Res<Time>,
     mut collision_events: EventWriter<AttackHit>,
 ) {
-    let mut ally_attacks: Vec<Entity> = vec![];
-    let mut enemy_attacks: Vec<Entity> = vec![];
+    let elapsed = time.elapsed_seconds();
 
-    for (entity, attack_party) in &attacks {
-        (if attack_party == &AttackParty::Ally {
-            &mut ally_attacks
-        } else {
-            &mut enemy_attacks
-        })
-        .push(entity);
-    }
+    for (attack_entity, attack, attack_party, mut attack_history) in &mut attacks {
+        for (collider_1, collider_2, intersecting) in
+            rapier_context.intersections_with(attack_entity)
+        {
+            if !intersecting {
+                continue;
+            }
 
-    for (warrior_entity, warrior_kinematic_output, is_ally_warrior) in &warriors {
-        for collision in &warrior_kinematic_output.collisions {
-            // query.get(collision.entity).and_then(|x| {
-            //     dbg!(x);
-            //     Ok(x)
-            // });
-            if (if is_ally_warrior {
-                &enemy_attacks
+            let hurtbox_entity = if collider_1 == attack_entity {
+                collider_2
             } else {
-                &ally_attacks
-            })
-            .contains(&collision.entity)
+                collider_1
+            };
+
+            let Ok(hurtbox_parent) = hurtboxes.get(hurtbox_entity) else {
+                continue;
+            };
+            let warrior_entity = hurtbox_parent.get();
+
+            let Ok((warrior_party, health, is_invulnerable)) = warriors.get(warrior_entity) else {
+                continue;
+            };
+
+            // the attack isn't used up on warriors it can't hurt, so it still hits on wake up
+            if health.is_knocked_out() || is_invulnerable {
+                continue;
+            }
+
+            // also keeps many hurtboxes of the same warrior from being hit at once
+            if attack_party == warrior_party
+                || !attack_history.try_hit(warrior_entity, attack, elapsed)
             {
-                collision_events.send(AttackHit {
-                    warrior_entity,
-                    attack_entity: collision.entity,
-                });
+                continue;
             }
+
+            collision_events.send(AttackHit {
+                warrior_entity,
+                attack_entity,
+            });
         }
     }
 }
 
+#[allow(clippy::type_complexity)]
 pub fn attack_hits(
     mut commands: Commands,
-    mut warrior_damaged_state: Query<&mut DamagedState>,
+    mut warriors: Query<(
+        &mut DamagedState,
+        &mut Health,
+        &mut StunMeter,
+        &mut Velocity,
+        Option<&mut Hitstop>,
+        &Weight,
+        &TnuaController,
+        Option<&Guard>,
+        Has<Invulnerable>,
+        Has<Launched>,
+    )>,
+    attacks: Query<(Entity, &Attack, &AttackOwner)>,
     mut attack_hit_events: EventReader<AttackHit>,
+    mut knocked_out_events: EventWriter<WarriorKnockedOut>,
+    mut combo_hit_events: EventWriter<ComboHit>,
 ) {
+    let mut landed_attacks = Vec::new();
+    let mut interrupted_warriors = Vec::new();
+    // a warrior may be hit, or hit others, more than once in the same frame
+    let mut warriors_hitstops: HashMap<Entity, f32> = HashMap::new();
+
     for attack_hit in attack_hit_events.read() {
-        // TODO: refactor to keep this logic to not happen for _every_ hit, but an aggregated one
-        commands
-            .entity(attack_hit.warrior_entity)
-            .remove::<DamagedTimer>()
-            .insert(DamagedTimer {
-                timer: Timer::from_seconds(1., TimerMode::Once),
+        let Ok((
+            //
+            mut damaged_state,
+            mut health,
+            mut stun_meter,
+            mut velocity,
+            mut hitstop,
+            weight,
+            tnua_controller,
+            guard,
+            is_invulnerable,
+            is_launched,
+        )) = warriors.get_mut(attack_hit.warrior_entity)
+        else {
+            continue;
+        };
+
+        if health.is_knocked_out() || is_invulnerable {
+            continue;
+        }
+
+        let Ok((_, attack, attack_owner)) = attacks.get(attack_hit.attack_entity) else {
+            continue;
+        };
+
+        landed_attacks.push((
+            attack_hit.attack_entity,
+            attack_owner.warrior_entity,
+            attack.hitstop,
+        ));
+
+        // the attacker and the victim freeze together
+        for warrior_entity in [attack_hit.warrior_entity, attack_owner.warrior_entity] {
+            let warrior_hitstop = warriors_hitstops.entry(warrior_entity).or_default();
+            *warrior_hitstop = warrior_hitstop.max(attack.hitstop);
+        }
+
+        // the victim's move is cut short along with the attacks it already spawned
+        if !interrupted_warriors.contains(&attack_hit.warrior_entity) {
+            interrupted_warriors.push(attack_hit.warrior_entity);
+            commands
+                .entity(attack_hit.warrior_entity)
+                .remove::<CurrentMove>();
+
+            for (owned_attack_entity, _, owned_attack_owner) in &attacks {
+                if owned_attack_owner.warrior_entity == attack_hit.warrior_entity {
+                    commands.entity(owned_attack_entity).despawn_recursive();
+                }
+            }
+        }
+
+        let is_blocked = guard.is_some_and(|guard| guard.blocks(&attack.height));
+        let damage = if is_blocked {
+            attack.chip_damage
+        } else {
+            attack.damage
+        };
+
+        if health.take_damage(damage) {
+            knocked_out_events.send(WarriorKnockedOut {
+                warrior_entity: attack_hit.warrior_entity,
             });
+        }
+
+        let mut warrior_commands = commands.entity(attack_hit.warrior_entity);
+        let mut knockback = attack.knockback / weight.0;
+        // grounded warriors are only knocked up by launchers, the rest just push them back
+        let launches = attack.launcher || is_launched || is_airborne(tnua_controller);
+
+        if !launches {
+            knockback.y = 0.;
+        }
 
-        *warrior_damaged_state
-            .get_mut(attack_hit.warrior_entity)
-            .unwrap() = DamagedState::Hit;
+        if is_blocked {
+            set_linvel(
+                &mut velocity,
+                hitstop.as_deref_mut(),
+                Vec2::new(knockback.x * BLOCK_PUSHBACK_RATIO, 0.),
+            );
+            warrior_commands
+                .insert(TnuaToggle::SenseOnly)
+                .remove::<DamagedTimer>()
+                .insert(DamagedTimer {
+                    timer: Timer::from_seconds(attack.blockstun, TimerMode::Once),
+                });
+            *damaged_state = DamagedState::Blocked;
+            continue;
+        }
+
+        combo_hit_events.send(ComboHit {
+            warrior_entity: attack_owner.warrior_entity,
+            victim_entity: attack_hit.warrior_entity,
+            damage,
+            is_combo: continues_combo(&damaged_state, is_launched),
+        });
+
+        // motors would brake the knockback, so the body is left to physics until recovered
+        set_linvel(&mut velocity, hitstop.as_deref_mut(), knockback);
+        warrior_commands.insert(TnuaToggle::SenseOnly);
+
+        if launches {
+            warrior_commands.insert(Launched);
+        }
+
+        // hits on a stunned warrior don't stack stun nor wake them up
+        if *damaged_state == DamagedState::Stunned {
+            continue;
+        }
+
+        warrior_commands.remove::<DamagedTimer>();
+
+        if stun_meter.add(attack.stun) {
+            warrior_commands.insert(StunnedTimer {
+                timer: Timer::from_seconds(STUNNED_SECONDS, TimerMode::Once),
+            });
+            *damaged_state = DamagedState::Stunned;
+            continue;
+        }
+
+        warrior_commands.insert(DamagedTimer {
+            timer: Timer::from_seconds(attack.hitstun, TimerMode::Once),
+        });
+        *damaged_state = DamagedState::Hit;
+    }
+
+    // the attack freezes along with the warriors
+    for (attack_entity, attacker_entity, hitstop) in landed_attacks {
+        // traded attacks were despawned with their interrupted move
+        if !interrupted_warriors.contains(&attacker_entity) {
+            start_hitstop(&mut commands.entity(attack_entity), None, None, hitstop);
+        }
+    }
+
+    for (warrior_entity, seconds) in warriors_hitstops {
+        if let Ok((_, _, _, mut velocity, mut hitstop, ..)) = warriors.get_mut(warrior_entity) {
+            start_hitstop(
+                &mut commands.entity(warrior_entity),
+                Some(&mut velocity),
+                hitstop.as_deref_mut(),
+                seconds,
+            );
+        }
+    }
+}
+
+pub fn knock_out_warriors(
+    mut knocked_out_events: EventReader<WarriorKnockedOut>,
+    mut warriors: Query<(
+        &mut WarriorPositionState,
+        &mut WarriorPositionStateTransition,
+    )>,
+) {
+    for knocked_out_event in knocked_out_events.read() {
+        let Ok((mut position_state, mut position_state_transition)) =
+            warriors.get_mut(knocked_out_event.warrior_entity)
+        else {
+            continue;
+        };
+
+        position_state_transition.previous = position_state.clone();
+        *position_state = WarriorPositionState::Fallen;
     }
 }
 
 pub fn damage_timer_finished(
+    mut commands: Commands,
     mut damage_timer_finished_events: EventReader<StateTimerFinished<DamagedTimer>>,
-    mut warrior_state_query: Query<&mut DamagedState>,
+    mut warrior_state_query: Query<(&mut DamagedState, Has<Launched>)>,
 ) {
-    //! this dont cover cases like: if damaged in air, then will have to fall to recover character control
     for damage_timer_finished_event in damage_timer_finished_events.read() {
-        *warrior_state_query
-            .get_mut(damage_timer_finished_event.0)
-            .unwrap() = DamagedState::None;
+        // warriors may be despawned by a new round before their events are read
+        let Ok((mut damaged_state, is_launched)) =
+            warrior_state_query.get_mut(damage_timer_finished_event.0)
+        else {
+            continue;
+        };
+        let Some(mut warrior_commands) = commands.get_entity(damage_timer_finished_event.0) else {
+            continue;
+        };
+
+        warrior_commands.remove::<DamagedTimer>();
+
+        // launched warriors only recover after landing
+        if is_launched {
+            continue;
+        }
+
+        warrior_commands.insert(TnuaToggle::Enabled);
+        *damaged_state = DamagedState::None;
+    }
+}
+
+pub fn stunned_timer_finished(
+    mut commands: Commands,
+    mut stunned_timer_finished_events: EventReader<StateTimerFinished<StunnedTimer>>,
+    mut warrior_state_query: Query<&mut DamagedState>,
+) {
+    for stunned_timer_finished_event in stunned_timer_finished_events.read() {
+        let Ok(mut damaged_state) = warrior_state_query.get_mut(stunned_timer_finished_event.0)
+        else {
+            continue;
+        };
+        let Some(mut warrior_commands) = commands.get_entity(stunned_timer_finished_event.0) else {
+            continue;
+        };
+
+        warrior_commands
+            .remove::<StunnedTimer>()
+            .insert(TnuaToggle::Enabled);
+        *damaged_state = DamagedState::None;
+    }
+}
+
+pub fn decay_stun_meters(mut stun_meters: Query<(&mut StunMeter, &DamagedState)>, time: Res<Time>) {
+    for (mut stun_meter, damaged_state) in &mut stun_meters {
+        if *damaged_state != DamagedState::None {
+            continue;
+        }
+
+        stun_meter.current = (stun_meter.current - stun_meter.decay * time.delta_seconds()).max(0.);
     }
 }
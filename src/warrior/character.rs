@@ -0,0 +1,198 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashSet};
+use serde::Deserialize;
+
+use super::{
+    Health, Speed, SpriteAnimationTimer, StunMeter, UpdateWarriorHitbox, Warrior, WarriorHitboxes,
+    WarriorPositionState, Weight,
+};
+
+/// Tweakable numbers of a character, reloaded live when its file changes
+#[derive(Debug, Asset, TypePath, Deserialize)]
+pub struct CharacterData {
+    pub speed: Speed,
+    pub health: f32,
+    pub stun_meter: StunMeterData,
+    pub weight: f32,
+    pub sprite_animation_seconds: f32,
+    pub hitboxes: WarriorHitboxes,
+}
+
+/// Used until the character file is loaded
+impl Default for CharacterData {
+    fn default() -> Self {
+        CharacterData {
+            speed: Speed {
+                walk: 180.,
+                jump: 450.,
+            },
+            health: 100.,
+            stun_meter: StunMeterData {
+                max: 100.,
+                decay: 10.,
+            },
+            weight: 1.,
+            sprite_animation_seconds: 0.75,
+            hitboxes: WarriorHitboxes::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StunMeterData {
+    pub max: f32,
+    /// Amount recovered per second while not being hit
+    pub decay: f32,
+}
+
+#[derive(Debug, Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct WarriorCharacter(pub Handle<CharacterData>);
+
+#[allow(clippy::type_complexity)]
+pub fn apply_characters_data(
+    mut character_data_events: EventReader<AssetEvent<CharacterData>>,
+    characters_data: Res<Assets<CharacterData>>,
+    mut warriors: Query<
+        (
+            Entity,
+            Ref<WarriorCharacter>,
+            &WarriorPositionState,
+            &mut Speed,
+            &mut Health,
+            &mut StunMeter,
+            &mut Weight,
+            &mut SpriteAnimationTimer,
+            &mut WarriorHitboxes,
+        ),
+        &Warrior,
+    >,
+    mut update_hitbox_event: EventWriter<UpdateWarriorHitbox>,
+) {
+    let changed_characters: HashSet<AssetId<CharacterData>> = character_data_events
+        .read()
+        .filter_map(|character_data_event| match character_data_event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    for (
+        //
+        warrior_entity,
+        warrior_character,
+        position_state,
+        mut speed,
+        mut health,
+        mut stun_meter,
+        mut weight,
+        mut sprite_animation_timer,
+        mut hitboxes,
+    ) in &mut warriors
+    {
+        let character_id = warrior_character.0.id();
+
+        // warriors spawned after their file was loaded also need it applied
+        if !warrior_character.is_added() && !changed_characters.contains(&character_id) {
+            continue;
+        }
+
+        let Some(character_data) = characters_data.get(character_id) else {
+            continue;
+        };
+
+        *speed = character_data.speed.clone();
+        *weight = Weight(character_data.weight);
+
+        // reloads keep the damage taken, fresh warriors start full
+        if warrior_character.is_added() {
+            *health = Health::new(character_data.health);
+        } else {
+            health.max = character_data.health;
+            health.current = health.current.min(health.max);
+        }
+
+        stun_meter.max = character_data.stun_meter.max;
+        stun_meter.decay = character_data.stun_meter.decay;
+
+        sprite_animation_timer
+            .timer
+            .set_duration(Duration::from_secs_f32(
+                character_data.sprite_animation_seconds,
+            ));
+        *hitboxes = character_data.hitboxes.clone();
+
+        update_hitbox_event.send(UpdateWarriorHitbox {
+            warrior_entity,
+            position_state: position_state.clone(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::asset::AssetPlugin;
+
+    use super::*;
+    use crate::player::walk_basis;
+
+    #[test]
+    fn reload_walk_speed_into_movement() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin {
+                watch_for_changes_override: Some(false),
+                ..default()
+            },
+        ))
+        .init_asset::<CharacterData>()
+        .add_event::<UpdateWarriorHitbox>()
+        .add_systems(Update, apply_characters_data);
+
+        let character_handle = app
+            .world
+            .resource_mut::<Assets<CharacterData>>()
+            .add(CharacterData::default());
+        let warrior_entity = app
+            .world
+            .spawn((
+                Warrior,
+                WarriorCharacter(character_handle.clone()),
+                WarriorPositionState::Idle,
+                Speed::default(),
+                Health::new(100.),
+                StunMeter::new(100., 10.),
+                Weight::default(),
+                SpriteAnimationTimer::default(),
+                WarriorHitboxes::default(),
+            ))
+            .id();
+        app.update();
+
+        let walk_velocity = |app: &App| {
+            let warrior = app.world.entity(warrior_entity);
+            walk_basis(
+                1.,
+                warrior.get::<Speed>().unwrap(),
+                warrior.get::<WarriorHitboxes>().unwrap(),
+            )
+            .desired_velocity
+            .x
+        };
+        assert_eq!(walk_velocity(&app), CharacterData::default().speed.walk);
+
+        app.world
+            .resource_mut::<Assets<CharacterData>>()
+            .get_mut(&character_handle)
+            .unwrap()
+            .speed
+            .walk = 300.;
+        // the modified event is only sent at the end of the frame
+        app.update();
+        app.update();
+
+        assert_eq!(walk_velocity(&app), 300.);
+    }
+}
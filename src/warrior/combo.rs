@@ -0,0 +1,359 @@
+use bevy::{ecs::query::Has, prelude::*};
+
+use super::{DamagedState, Health, Launched, Thrown};
+
+/// A new combo this soon after the last one on the same victim follows it up
+pub const COMBO_FOLLOW_UP_WINDOW_SECONDS: f32 = 0.5;
+
+/// Combo of the attacker, kept after it ends so it can still be shown
+#[derive(Debug, Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct ComboState {
+    pub hits: u32,
+    pub damage: f32,
+    /// Set while the victim is still in hitstun
+    pub victim_entity: Option<Entity>,
+    /// Started on the same victim right after the previous combo ended
+    pub follows_recent_combo: bool,
+    /// Elapsed seconds when the last combo ended
+    pub ended_at: Option<f32>,
+    last_victim_entity: Option<Entity>,
+}
+
+/// Hit that went through the guard, sent by the attacker
+#[derive(Event)]
+pub struct ComboHit {
+    pub warrior_entity: Entity,
+    pub victim_entity: Entity,
+    pub damage: f32,
+    /// The victim was still in hitstun, so the hit continues the combo
+    pub is_combo: bool,
+}
+
+#[derive(Event)]
+pub struct ComboStarted {
+    pub warrior_entity: Entity,
+    pub victim_entity: Entity,
+}
+
+#[derive(Event)]
+pub struct ComboEnded {
+    pub warrior_entity: Entity,
+    pub victim_entity: Entity,
+    pub hits: u32,
+    pub damage: f32,
+    pub follows_recent_combo: bool,
+}
+
+pub fn count_combo_hits(
+    mut combo_hit_events: EventReader<ComboHit>,
+    mut attackers: Query<&mut ComboState>,
+    time: Res<Time>,
+    mut combo_started_events: EventWriter<ComboStarted>,
+    mut combo_ended_events: EventWriter<ComboEnded>,
+) {
+    for combo_hit in combo_hit_events.read() {
+        let Ok(mut combo_state) = attackers.get_mut(combo_hit.warrior_entity) else {
+            continue;
+        };
+
+        if combo_hit.is_combo && combo_state.victim_entity == Some(combo_hit.victim_entity) {
+            combo_state.hits += 1;
+            combo_state.damage += combo_hit.damage;
+            continue;
+        }
+
+        // a fresh hit while the last combo still runs, e.g. on another victim, closes it
+        if let Some(victim_entity) = combo_state.victim_entity {
+            end_combo(
+                combo_hit.warrior_entity,
+                victim_entity,
+                &mut combo_state,
+                time.elapsed_seconds(),
+                &mut combo_ended_events,
+            );
+        }
+
+        let follows_recent_combo = combo_state.last_victim_entity == Some(combo_hit.victim_entity)
+            && combo_state.ended_at.is_some_and(|ended_at| {
+                time.elapsed_seconds() - ended_at <= COMBO_FOLLOW_UP_WINDOW_SECONDS
+            });
+
+        *combo_state = ComboState {
+            hits: 1,
+            damage: combo_hit.damage,
+            victim_entity: Some(combo_hit.victim_entity),
+            follows_recent_combo,
+            ended_at: None,
+            last_victim_entity: Some(combo_hit.victim_entity),
+        };
+
+        combo_started_events.send(ComboStarted {
+            warrior_entity: combo_hit.warrior_entity,
+            victim_entity: combo_hit.victim_entity,
+        });
+    }
+}
+
+/// Hits on a victim still reeling from the last one, or in the air, extend the combo
+pub fn continues_combo(damaged_state: &DamagedState, is_launched: bool) -> bool {
+    is_launched || matches!(*damaged_state, DamagedState::Hit | DamagedState::Stunned)
+}
+
+/// Combos end once the victim can act again, or is knocked out
+#[allow(clippy::type_complexity)]
+pub fn end_combos(
+    mut attackers: Query<(Entity, &mut ComboState)>,
+    victims: Query<(&DamagedState, &Health, Has<Launched>, Has<Thrown>)>,
+    time: Res<Time>,
+    mut combo_ended_events: EventWriter<ComboEnded>,
+) {
+    for (attacker_entity, mut combo_state) in &mut attackers {
+        let Some(victim_entity) = combo_state.victim_entity else {
+            continue;
+        };
+
+        let is_combo_over = victims.get(victim_entity).map_or(
+            true,
+            |(damaged_state, health, is_launched, is_thrown)| {
+                health.is_knocked_out()
+                    || (matches!(*damaged_state, DamagedState::None | DamagedState::Blocked)
+                        && !is_launched
+                        && !is_thrown)
+            },
+        );
+
+        if is_combo_over {
+            end_combo(
+                attacker_entity,
+                victim_entity,
+                &mut combo_state,
+                time.elapsed_seconds(),
+                &mut combo_ended_events,
+            );
+        }
+    }
+}
+
+fn end_combo(
+    attacker_entity: Entity,
+    victim_entity: Entity,
+    combo_state: &mut ComboState,
+    elapsed: f32,
+    combo_ended_events: &mut EventWriter<ComboEnded>,
+) {
+    combo_state.victim_entity = None;
+    combo_state.ended_at = Some(elapsed);
+
+    combo_ended_events.send(ComboEnded {
+        warrior_entity: attacker_entity,
+        victim_entity,
+        hits: combo_state.hits,
+        damage: combo_state.damage,
+        follows_recent_combo: combo_state.follows_recent_combo,
+    });
+}
+
+/// Traces combos in the debug log, there is no HUD for them yet
+pub fn log_combos(
+    mut combo_started_events: EventReader<ComboStarted>,
+    mut combo_ended_events: EventReader<ComboEnded>,
+    names: Query<&Name>,
+) {
+    let name_of = |entity: Entity| names.get(entity).map_or("Warrior", Name::as_str);
+
+    for combo_started in combo_started_events.read() {
+        debug!(
+            "{} started a combo on {}",
+            name_of(combo_started.warrior_entity),
+            name_of(combo_started.victim_entity)
+        );
+    }
+
+    for combo_ended in combo_ended_events.read() {
+        if combo_ended.hits < 2 {
+            continue;
+        }
+
+        debug!(
+            "{} landed {} HITS on {}, {} damage{}",
+            name_of(combo_ended.warrior_entity),
+            combo_ended.hits,
+            name_of(combo_ended.victim_entity),
+            combo_ended.damage,
+            if combo_ended.follows_recent_combo {
+                ", right after another combo on them"
+            } else {
+                ""
+            }
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    struct ComboTest {
+        app: App,
+        attacker_entity: Entity,
+        victim_entity: Entity,
+    }
+
+    impl ComboTest {
+        fn new() -> Self {
+            let mut app = App::new();
+            app.init_resource::<Time>()
+                .add_event::<ComboHit>()
+                .add_event::<ComboStarted>()
+                .add_event::<ComboEnded>()
+                .add_systems(
+                    Update,
+                    (count_combo_hits, end_combos.after(count_combo_hits)),
+                );
+
+            let attacker_entity = app.world.spawn(ComboState::default()).id();
+            let victim_entity = app
+                .world
+                .spawn((DamagedState::None, Health::new(100.)))
+                .id();
+
+            ComboTest {
+                app,
+                attacker_entity,
+                victim_entity,
+            }
+        }
+
+        /// Same as the attack hits, the victim is left in hitstun
+        fn hit(&mut self, damage: f32) {
+            let victim = self.app.world.entity(self.victim_entity);
+            let is_combo = continues_combo(
+                victim.get::<DamagedState>().unwrap(),
+                victim.contains::<Launched>(),
+            );
+
+            self.app.world.send_event(ComboHit {
+                warrior_entity: self.attacker_entity,
+                victim_entity: self.victim_entity,
+                damage,
+                is_combo,
+            });
+            self.app
+                .world
+                .get_mut::<Health>(self.victim_entity)
+                .unwrap()
+                .take_damage(damage);
+            *self
+                .app
+                .world
+                .get_mut::<DamagedState>(self.victim_entity)
+                .unwrap() = DamagedState::Hit;
+            self.app.update();
+        }
+
+        fn recover(&mut self) {
+            *self
+                .app
+                .world
+                .get_mut::<DamagedState>(self.victim_entity)
+                .unwrap() = DamagedState::None;
+            self.app.update();
+        }
+
+        fn wait(&mut self, seconds: f32) {
+            self.app
+                .world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(seconds));
+        }
+
+        fn combo_state(&self) -> &ComboState {
+            self.app
+                .world
+                .get::<ComboState>(self.attacker_entity)
+                .unwrap()
+        }
+    }
+
+    #[test]
+    fn continue_combo_on_reeling_or_launched_victim() {
+        assert!(continues_combo(&DamagedState::Hit, false));
+        assert!(continues_combo(&DamagedState::Stunned, false));
+        // launched warriors keep reeling until they land
+        assert!(continues_combo(&DamagedState::None, true));
+        assert!(!continues_combo(&DamagedState::None, false));
+        assert!(!continues_combo(&DamagedState::Blocked, false));
+    }
+
+    #[test]
+    fn continue_combo_while_in_hitstun() {
+        let mut combo_test = ComboTest::new();
+
+        combo_test.hit(10.);
+        combo_test.hit(5.);
+        combo_test.hit(5.);
+
+        let combo_state = combo_test.combo_state();
+        assert_eq!(combo_state.hits, 3);
+        assert_eq!(combo_state.damage, 20.);
+        assert_eq!(combo_state.victim_entity, Some(combo_test.victim_entity));
+        assert!(combo_state.ended_at.is_none());
+    }
+
+    #[test]
+    fn end_combo_on_recovery() {
+        let mut combo_test = ComboTest::new();
+
+        combo_test.hit(10.);
+        combo_test.hit(10.);
+        combo_test.recover();
+
+        let combo_state = combo_test.combo_state();
+        assert_eq!(combo_state.hits, 2);
+        assert!(combo_state.victim_entity.is_none());
+        assert!(combo_state.ended_at.is_some());
+    }
+
+    #[test]
+    fn end_combo_on_knockout() {
+        let mut combo_test = ComboTest::new();
+
+        combo_test.hit(60.);
+        combo_test.hit(60.);
+
+        let combo_state = combo_test.combo_state();
+        assert_eq!(combo_state.hits, 2);
+        assert!(combo_state.victim_entity.is_none());
+    }
+
+    #[test]
+    fn flag_combo_started_right_after_the_last_one() {
+        let mut combo_test = ComboTest::new();
+
+        combo_test.hit(10.);
+        combo_test.recover();
+        combo_test.wait(COMBO_FOLLOW_UP_WINDOW_SECONDS / 2.);
+        combo_test.hit(10.);
+
+        let combo_state = combo_test.combo_state();
+        assert_eq!(combo_state.hits, 1);
+        assert!(combo_state.follows_recent_combo);
+    }
+
+    #[test]
+    fn keep_combo_started_after_the_follow_up_window_clean() {
+        let mut combo_test = ComboTest::new();
+
+        combo_test.hit(10.);
+        combo_test.recover();
+        combo_test.wait(COMBO_FOLLOW_UP_WINDOW_SECONDS * 2.);
+        combo_test.hit(10.);
+
+        let combo_state = combo_test.combo_state();
+        assert_eq!(combo_state.hits, 1);
+        assert!(!combo_state.follows_recent_combo);
+    }
+}
@@ -0,0 +1,78 @@
+use bevy::{ecs::query::Has, prelude::*};
+use bevy_rapier2d::prelude::*;
+
+use super::{
+    AttackParty, CurrentMove, FacingPosition, UpdateWarriorHitbox, Warrior, WarriorPositionState,
+};
+
+/// Below this the warriors are on top of each other or barely moving, so no side is picked
+const FACING_DEAD_ZONE: f32 = 1.;
+
+/// Faces the nearest enemy warrior, or the moving direction when there is none
+#[allow(clippy::type_complexity)]
+pub fn face_opponents(
+    mut warriors: Query<
+        (
+            Entity,
+            &Transform,
+            &Velocity,
+            &AttackParty,
+            &WarriorPositionState,
+            &mut FacingPosition,
+            Has<CurrentMove>,
+        ),
+        &Warrior,
+    >,
+    mut update_hitbox_event: EventWriter<UpdateWarriorHitbox>,
+) {
+    let positions: Vec<(Entity, f32, AttackParty)> = warriors
+        .iter()
+        .map(|(entity, transform, _, attack_party, ..)| {
+            (entity, transform.translation.x, attack_party.clone())
+        })
+        .collect();
+
+    for (
+        //
+        entity,
+        transform,
+        velocity,
+        attack_party,
+        position_state,
+        mut facing_position,
+        is_performing_move,
+    ) in &mut warriors
+    {
+        // sides only switch between moves and while on the ground
+        if is_performing_move || *position_state == WarriorPositionState::Jumping {
+            continue;
+        }
+
+        let x = transform.translation.x;
+        let nearest_enemy_x = positions
+            .iter()
+            .filter(|(other_entity, _, other_party)| {
+                *other_entity != entity && other_party != attack_party
+            })
+            .map(|(_, other_x, _)| *other_x)
+            .min_by(|a, b| (a - x).abs().total_cmp(&(b - x).abs()));
+
+        let towards_x = nearest_enemy_x.map_or(velocity.linvel.x, |enemy_x| enemy_x - x);
+
+        let new_facing_position = if towards_x > FACING_DEAD_ZONE {
+            FacingPosition::Right
+        } else if towards_x < -FACING_DEAD_ZONE {
+            FacingPosition::Left
+        } else {
+            continue;
+        };
+
+        if *facing_position != new_facing_position {
+            *facing_position = new_facing_position;
+            update_hitbox_event.send(UpdateWarriorHitbox {
+                warrior_entity: entity,
+                position_state: position_state.clone(),
+            });
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+/// Where the attack must be blocked
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Reflect)]
+pub enum AttackHeight {
+    /// Overheads, only blocked standing
+    High,
+    #[default]
+    Mid,
+    /// Sweeps, only blocked crouching
+    Low,
+}
+
+/// Held back (or block) while able to act, so incoming attacks are blocked
+#[derive(Debug, Component, Clone, Default, Reflect, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum Guard {
+    #[default]
+    Standing,
+    Crouching,
+}
+
+impl Guard {
+    pub fn blocks(&self, attack_height: &AttackHeight) -> bool {
+        !matches!(
+            (self, attack_height),
+            (Guard::Standing, AttackHeight::Low) | (Guard::Crouching, AttackHeight::High)
+        )
+    }
+}
@@ -1,20 +1,28 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
 
 use crate::assets::WARRIOR_IN_GAME_SPRITE_SIZE;
 
 use super::{
-    states::{WarriorPositionState, WarriorPositionStateTransition},
+    states::{FacingPosition, WarriorPositionState},
     Warrior,
 };
 
+pub const PUSHBOX_GROUP: Group = Group::GROUP_1;
+pub const HURTBOX_GROUP: Group = Group::GROUP_2;
+pub const ATTACK_GROUP: Group = Group::GROUP_3;
+pub const SCENERY_GROUP: Group = Group::GROUP_4;
+
+/// Solid collider that stands on the floor and pushes other warriors
 #[derive(Debug, Component, Reflect, Default)]
 #[reflect(Component)]
-pub struct Hitbox;
+pub struct Pushbox;
 
+/// Sensor where the warrior can be hit, a pose may have many of them
 #[derive(Debug, Component, Reflect, Default)]
 #[reflect(Component)]
-pub struct CrouchHitbox;
+pub struct Hurtbox;
 
 #[derive(Event)]
 pub struct UpdateWarriorHitbox {
@@ -22,70 +30,180 @@ pub struct UpdateWarriorHitbox {
     pub position_state: WarriorPositionState,
 }
 
-pub trait PositionStateHitbox {
-    const STOOD_UP_HITBOX_HALF_EXTENT: (f32, f32);
-    const CROUCH_HITBOX_HALF_EXTENT: (f32, f32);
-    fn get_position_hitbox(&self) -> (f32, f32);
+/// Half extents of the warrior's boxes, loaded from its character data
+#[derive(Debug, Component, Clone, Reflect, Deserialize)]
+#[reflect(Component)]
+pub struct WarriorHitboxes {
+    pub stood_up_half_extent: (f32, f32),
+    pub crouch_half_extent: (f32, f32),
 }
 
-impl PositionStateHitbox for WarriorPositionState {
-    const STOOD_UP_HITBOX_HALF_EXTENT: (f32, f32) = (
-        WARRIOR_IN_GAME_SPRITE_SIZE.x / 4.,
-        WARRIOR_IN_GAME_SPRITE_SIZE.y / 2.,
-    );
+impl Default for WarriorHitboxes {
+    fn default() -> Self {
+        WarriorHitboxes {
+            stood_up_half_extent: (
+                WARRIOR_IN_GAME_SPRITE_SIZE.x / 4.,
+                WARRIOR_IN_GAME_SPRITE_SIZE.y / 2.,
+            ),
+            crouch_half_extent: (
+                WARRIOR_IN_GAME_SPRITE_SIZE.x / 2.5,
+                WARRIOR_IN_GAME_SPRITE_SIZE.y / 4.,
+            ),
+        }
+    }
+}
+
+/// Rectangle relative to the warrior's center
+#[derive(Debug, Clone, Reflect, Default)]
+pub struct HitboxRect {
+    pub half_extents: Vec2,
+    pub offset: Vec2,
+}
 
-    const CROUCH_HITBOX_HALF_EXTENT: (f32, f32) = (
-        WARRIOR_IN_GAME_SPRITE_SIZE.x / 2.5,
-        WARRIOR_IN_GAME_SPRITE_SIZE.y / 4.,
-    );
+impl HitboxRect {
+    /// Keeps the rectangle's bottom on the warrior's feet
+    fn on_feet(half_extents: (f32, f32), hitboxes: &WarriorHitboxes) -> Self {
+        HitboxRect {
+            half_extents: Vec2::new(half_extents.0, half_extents.1),
+            offset: Vec2::new(0., half_extents.1 - hitboxes.stood_up_half_extent.1),
+        }
+    }
+
+    pub fn collider(&self) -> Collider {
+        Collider::cuboid(self.half_extents.x, self.half_extents.y)
+    }
 
-    fn get_position_hitbox(&self) -> (f32, f32) {
+    /// Mirrors the offset to where the warrior is facing
+    pub fn transform(&self, facing_position: &FacingPosition) -> Transform {
+        Transform::from_xyz(
+            self.offset.x * facing_position.direction(),
+            self.offset.y,
+            0.,
+        )
+    }
+}
+
+pub trait PositionStateHitbox {
+    fn get_position_hitbox(&self, hitboxes: &WarriorHitboxes) -> (f32, f32);
+    fn get_position_pushbox(&self, hitboxes: &WarriorHitboxes) -> HitboxRect;
+    fn get_position_hurtboxes(&self, hitboxes: &WarriorHitboxes) -> Vec<HitboxRect>;
+}
+
+impl PositionStateHitbox for WarriorPositionState {
+    fn get_position_hitbox(&self, hitboxes: &WarriorHitboxes) -> (f32, f32) {
         match self {
             WarriorPositionState::Idle
             | WarriorPositionState::Walking
-            | WarriorPositionState::Jumping => Self::STOOD_UP_HITBOX_HALF_EXTENT,
-            WarriorPositionState::Crouching => Self::CROUCH_HITBOX_HALF_EXTENT,
+            | WarriorPositionState::Jumping => hitboxes.stood_up_half_extent,
+            WarriorPositionState::Crouching | WarriorPositionState::GettingUp => {
+                hitboxes.crouch_half_extent
+            }
             WarriorPositionState::Fallen => (
-                Self::STOOD_UP_HITBOX_HALF_EXTENT.1,
-                Self::STOOD_UP_HITBOX_HALF_EXTENT.0,
+                hitboxes.stood_up_half_extent.1,
+                hitboxes.stood_up_half_extent.0,
             ),
         }
     }
+
+    fn get_position_pushbox(&self, hitboxes: &WarriorHitboxes) -> HitboxRect {
+        HitboxRect::on_feet(self.get_position_hitbox(hitboxes), hitboxes)
+    }
+
+    fn get_position_hurtboxes(&self, hitboxes: &WarriorHitboxes) -> Vec<HitboxRect> {
+        let (half_width, half_height) = hitboxes.stood_up_half_extent;
+        let head_half_height = half_height / 5.;
+
+        let head = HitboxRect {
+            half_extents: Vec2::new(half_width * 0.6, head_half_height),
+            offset: Vec2::new(0., half_height - head_half_height),
+        };
+
+        match self {
+            WarriorPositionState::Idle | WarriorPositionState::Walking => vec![
+                head,
+                HitboxRect::on_feet((half_width, half_height - head_half_height), hitboxes),
+            ],
+            // legs are tucked while in the air
+            WarriorPositionState::Jumping => vec![
+                head,
+                HitboxRect {
+                    half_extents: Vec2::new(half_width, half_height * 0.6),
+                    offset: Vec2::new(0., half_height * 0.2 - head_half_height),
+                },
+            ],
+            WarriorPositionState::Crouching
+            | WarriorPositionState::GettingUp
+            | WarriorPositionState::Fallen => vec![self.get_position_pushbox(hitboxes)],
+        }
+    }
+}
+
+pub fn spawn_hitbox_children(
+    parent: &mut ChildBuilder,
+    position_state: &WarriorPositionState,
+    facing_position: &FacingPosition,
+    hitboxes: &WarriorHitboxes,
+) {
+    let pushbox = position_state.get_position_pushbox(hitboxes);
+
+    parent.spawn((
+        Name::new("Pushbox"),
+        Pushbox,
+        pushbox.collider(),
+        CollisionGroups::new(PUSHBOX_GROUP, PUSHBOX_GROUP | SCENERY_GROUP),
+        TransformBundle::from_transform(pushbox.transform(facing_position)),
+    ));
+
+    spawn_hurtboxes(parent, position_state, facing_position, hitboxes);
+}
+
+fn spawn_hurtboxes(
+    parent: &mut ChildBuilder,
+    position_state: &WarriorPositionState,
+    facing_position: &FacingPosition,
+    hitboxes: &WarriorHitboxes,
+) {
+    for hurtbox in position_state.get_position_hurtboxes(hitboxes) {
+        parent.spawn((
+            Name::new("Hurtbox"),
+            Hurtbox,
+            hurtbox.collider(),
+            Sensor,
+            // sensors would otherwise change the body's mass on every pose
+            ColliderMassProperties::Density(0.),
+            CollisionGroups::new(HURTBOX_GROUP, ATTACK_GROUP),
+            TransformBundle::from_transform(hurtbox.transform(facing_position)),
+        ));
+    }
 }
 
 pub fn update_warriors_hitbox(
+    mut commands: Commands,
     mut update_hitbox_events: EventReader<UpdateWarriorHitbox>,
-    mut warrior_collider_components: Query<
-        (
-            &mut Collider,
-            &mut Transform,
-            &WarriorPositionStateTransition,
-        ),
-        &Warrior,
-    >,
+    warriors: Query<(&Children, &WarriorHitboxes, &FacingPosition), With<Warrior>>,
+    mut pushboxes: Query<(&mut Collider, &mut Transform), With<Pushbox>>,
+    hurtboxes: Query<Entity, With<Hurtbox>>,
 ) {
-    // TODO: consider using collision groups and child entities for crouch hitbox
-    //! bugs with walking and crouching, jumping and crouching
-
     for warrior_hitbox_update in update_hitbox_events.read() {
-        let (
-            //
-            mut warrior_collider,
-            mut transform,
-            position_state_transition,
-        ) = warrior_collider_components
-            .get_mut(warrior_hitbox_update.warrior_entity)
-            .unwrap();
-
+        let warrior_entity = warrior_hitbox_update.warrior_entity;
         let position_state = &warrior_hitbox_update.position_state;
+        let Ok((warrior_children, hitboxes, facing_position)) = warriors.get(warrior_entity) else {
+            continue;
+        };
+        let pushbox = position_state.get_position_pushbox(hitboxes);
 
-        let half_extents = position_state.get_position_hitbox();
-        *warrior_collider = Collider::cuboid(half_extents.0, half_extents.1);
-
-        if position_state == &WarriorPositionState::Crouching {
-            transform.translation.y -= WarriorPositionState::Crouching.get_position_hitbox().1;
-        } else if position_state_transition.previous == WarriorPositionState::Crouching {
-            transform.translation.y += WarriorPositionState::Crouching.get_position_hitbox().1;
+        // the body stays still, only its colliders move around it
+        for &child in warrior_children {
+            if let Ok((mut pushbox_collider, mut pushbox_transform)) = pushboxes.get_mut(child) {
+                *pushbox_collider = pushbox.collider();
+                *pushbox_transform = pushbox.transform(facing_position);
+            } else if hurtboxes.contains(child) {
+                commands.entity(child).despawn_recursive();
+            }
         }
+
+        commands.entity(warrior_entity).with_children(|parent| {
+            spawn_hurtboxes(parent, position_state, facing_position, hitboxes)
+        });
     }
 }
@@ -0,0 +1,72 @@
+use bevy::{ecs::system::EntityCommands, prelude::*};
+use bevy_rapier2d::prelude::*;
+
+/// Freezes the attacker, the victim and the attack for a few frames when a hit lands
+#[derive(Debug, Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Hitstop {
+    pub timer: Timer,
+    /// Given back to the body once the hitstop is over
+    pub linvel: Vec2,
+}
+
+/// Also stops the body, keeping its velocity for when it resumes. A body already in hitstop
+/// keeps the velocity saved the first time and only has its timer extended
+pub fn start_hitstop(
+    entity_commands: &mut EntityCommands,
+    velocity: Option<&mut Velocity>,
+    hitstop: Option<&mut Hitstop>,
+    seconds: f32,
+) {
+    if seconds <= 0. {
+        return;
+    }
+
+    if let Some(hitstop) = hitstop {
+        if hitstop.timer.remaining_secs() < seconds {
+            hitstop.timer = Timer::from_seconds(seconds, TimerMode::Once);
+        }
+        return;
+    }
+
+    let linvel = velocity.map_or(Vec2::ZERO, |velocity| {
+        std::mem::replace(&mut velocity.linvel, Vec2::ZERO)
+    });
+
+    // the attack may be despawned by its timer this same frame
+    entity_commands.try_insert((
+        Hitstop {
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+            linvel,
+        },
+        RigidBodyDisabled,
+    ));
+}
+
+/// Moves the body, or sets where it goes once its hitstop is over
+pub fn set_linvel(velocity: &mut Velocity, hitstop: Option<&mut Hitstop>, linvel: Vec2) {
+    match hitstop {
+        Some(hitstop) => hitstop.linvel = linvel,
+        None => velocity.linvel = linvel,
+    }
+}
+
+pub fn end_hitstops(
+    mut commands: Commands,
+    mut hitstops: Query<(Entity, &mut Hitstop, Option<&mut Velocity>)>,
+    time: Res<Time>,
+) {
+    for (entity, mut hitstop, velocity) in &mut hitstops {
+        if !hitstop.timer.tick(time.delta()).finished() {
+            continue;
+        }
+
+        commands
+            .entity(entity)
+            .remove::<(Hitstop, RigidBodyDisabled)>();
+
+        if let Some(mut velocity) = velocity {
+            velocity.linvel = hitstop.linvel;
+        }
+    }
+}
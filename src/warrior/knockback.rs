@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::scenery::Floor;
+
+use super::{DamagedState, DamagedTimer, Health, Hitstop, Pushbox, Warrior, WarriorKnockedDown};
+
+/// Warrior hit while in the air, only recovers after landing
+#[derive(Debug, Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Launched;
+
+#[allow(clippy::type_complexity)]
+pub fn land_launched_warriors(
+    mut commands: Commands,
+    mut launched_warriors: Query<
+        (Entity, &Velocity, &Health, &mut DamagedState),
+        (With<Launched>, With<Warrior>, Without<Hitstop>),
+    >,
+    pushboxes: Query<(Entity, &Parent), With<Pushbox>>,
+    floors: Query<Entity, With<Floor>>,
+    rapier_context: Res<RapierContext>,
+    mut knocked_down_events: EventWriter<WarriorKnockedDown>,
+) {
+    for (pushbox_entity, pushbox_parent) in &pushboxes {
+        let Ok((
+            //
+            warrior_entity,
+            velocity,
+            health,
+            mut damaged_state,
+        )) = launched_warriors.get_mut(pushbox_parent.get())
+        else {
+            continue;
+        };
+
+        if velocity.linvel.y > 0. {
+            continue;
+        }
+
+        let touches_floor = floors.iter().any(|floor_entity| {
+            rapier_context
+                .contact_pair(pushbox_entity, floor_entity)
+                .is_some_and(|contact_pair| contact_pair.has_any_active_contacts())
+        });
+
+        if !touches_floor {
+            continue;
+        }
+
+        commands
+            .entity(warrior_entity)
+            .remove::<(Launched, DamagedTimer)>();
+
+        *damaged_state = DamagedState::None;
+
+        // knocked out warriors are already fallen for good
+        if !health.is_knocked_out() {
+            knocked_down_events.send(WarriorKnockedDown { warrior_entity });
+        }
+    }
+}
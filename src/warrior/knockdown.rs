@@ -0,0 +1,156 @@
+use bevy::prelude::*;
+use bevy_tnua::TnuaToggle;
+
+use super::{
+    FallenTimer, GettingUpTimer, StateTimerFinished, WarriorPositionState,
+    WarriorPositionStateTransition,
+};
+
+/// Seconds spent in each step of the knockdown, editable from the inspector
+#[derive(Debug, Resource, Reflect)]
+#[reflect(Resource)]
+pub struct KnockdownSettings {
+    pub fallen_seconds: f32,
+    pub quick_rise_seconds: f32,
+    pub delayed_rise_extra_seconds: f32,
+    pub getting_up_seconds: f32,
+}
+
+impl Default for KnockdownSettings {
+    fn default() -> Self {
+        KnockdownSettings {
+            fallen_seconds: 1.,
+            quick_rise_seconds: 0.4,
+            delayed_rise_extra_seconds: 0.5,
+            getting_up_seconds: 0.3,
+        }
+    }
+}
+
+/// Can't be hit by attacks
+#[derive(Debug, Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Invulnerable;
+
+#[derive(Event)]
+pub struct WarriorKnockedDown {
+    pub warrior_entity: Entity,
+}
+
+pub enum RiseKind {
+    Quick,
+    Delayed,
+}
+
+impl FallenTimer {
+    /// Only the first rise input while fallen is taken into account
+    pub fn choose_rise(&mut self, rise_kind: RiseKind, knockdown_settings: &KnockdownSettings) {
+        if self.rise_chosen {
+            return;
+        }
+
+        self.rise_chosen = true;
+
+        let duration = match rise_kind {
+            RiseKind::Quick => knockdown_settings.quick_rise_seconds,
+            RiseKind::Delayed => {
+                knockdown_settings.fallen_seconds + knockdown_settings.delayed_rise_extra_seconds
+            }
+        };
+        self.timer
+            .set_duration(std::time::Duration::from_secs_f32(duration));
+    }
+}
+
+pub fn knock_down_warriors(
+    mut commands: Commands,
+    mut knocked_down_events: EventReader<WarriorKnockedDown>,
+    mut warriors: Query<(
+        &mut WarriorPositionState,
+        &mut WarriorPositionStateTransition,
+    )>,
+    knockdown_settings: Res<KnockdownSettings>,
+) {
+    for knocked_down_event in knocked_down_events.read() {
+        // warriors may be despawned by a new round before their events are read
+        let Ok((mut position_state, mut position_state_transition)) =
+            warriors.get_mut(knocked_down_event.warrior_entity)
+        else {
+            continue;
+        };
+        let Some(mut warrior_commands) = commands.get_entity(knocked_down_event.warrior_entity)
+        else {
+            continue;
+        };
+
+        warrior_commands.insert((
+            FallenTimer {
+                timer: Timer::from_seconds(knockdown_settings.fallen_seconds, TimerMode::Once),
+                rise_chosen: false,
+            },
+            Invulnerable,
+            TnuaToggle::SenseOnly,
+        ));
+
+        position_state_transition.previous = position_state.clone();
+        *position_state = WarriorPositionState::Fallen;
+    }
+}
+
+pub fn fallen_timer_finished(
+    mut commands: Commands,
+    mut fallen_timer_finished_events: EventReader<StateTimerFinished<FallenTimer>>,
+    mut warriors: Query<(
+        &mut WarriorPositionState,
+        &mut WarriorPositionStateTransition,
+    )>,
+    knockdown_settings: Res<KnockdownSettings>,
+) {
+    for fallen_timer_finished_event in fallen_timer_finished_events.read() {
+        let Ok((mut position_state, mut position_state_transition)) =
+            warriors.get_mut(fallen_timer_finished_event.0)
+        else {
+            continue;
+        };
+        let Some(mut warrior_commands) = commands.get_entity(fallen_timer_finished_event.0) else {
+            continue;
+        };
+
+        warrior_commands
+            .remove::<FallenTimer>()
+            .insert(GettingUpTimer {
+                timer: Timer::from_seconds(knockdown_settings.getting_up_seconds, TimerMode::Once),
+            });
+
+        position_state_transition.previous = position_state.clone();
+        *position_state = WarriorPositionState::GettingUp;
+    }
+}
+
+pub fn getting_up_timer_finished(
+    mut commands: Commands,
+    mut getting_up_timer_finished_events: EventReader<StateTimerFinished<GettingUpTimer>>,
+    mut warriors: Query<(
+        &mut WarriorPositionState,
+        &mut WarriorPositionStateTransition,
+    )>,
+) {
+    for getting_up_timer_finished_event in getting_up_timer_finished_events.read() {
+        let Ok((mut position_state, mut position_state_transition)) =
+            warriors.get_mut(getting_up_timer_finished_event.0)
+        else {
+            continue;
+        };
+        let Some(mut warrior_commands) = commands.get_entity(getting_up_timer_finished_event.0)
+        else {
+            continue;
+        };
+
+        warrior_commands
+            .remove::<(GettingUpTimer, Invulnerable)>()
+            .insert(TnuaToggle::Enabled);
+
+        position_state_transition.previous = position_state.clone();
+        *position_state = WarriorPositionState::Idle;
+    }
+}
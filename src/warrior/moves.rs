@@ -0,0 +1,275 @@
+use bevy::{ecs::query::Has, prelude::*};
+use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
+
+use crate::game_state::{AppState, DespawnOnExit};
+use crate::input::{InputBuffer, Motion};
+
+use super::{
+    Attack, AttackHeight, AttackHistory, AttackOwner, AttackParty, AttackTimer, AttackTriggered,
+    DamagedState, FacingPosition, Hitstop, ThrowAttempted, ThrowBox, Warrior, ATTACK_GROUP,
+    FRAME_DURATION, HURTBOX_GROUP,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Reflect)]
+pub enum MoveInput {
+    LightAttack,
+    HeavyAttack,
+    Throw,
+}
+
+#[derive(Debug, Clone, Deserialize, Reflect)]
+pub struct MoveHitbox {
+    /// Counted from the first active frame of the move
+    pub start_frame: u32,
+    pub duration_frames: u32,
+    pub half_extents: (f32, f32),
+    /// Relative to the warrior's center while facing right
+    pub offset: (f32, f32),
+}
+
+#[derive(Debug, Clone, Deserialize, Reflect)]
+pub struct MoveDefinition {
+    pub name: String,
+    pub input: MoveInput,
+    /// Makes it a special move, performed by the motion followed by the input
+    #[serde(default)]
+    pub motion: Option<Motion>,
+    pub startup_frames: u32,
+    pub active_frames: u32,
+    pub recovery_frames: u32,
+    pub hitboxes: Vec<MoveHitbox>,
+    pub damage: f32,
+    pub stun: f32,
+    /// Pointing forward, gets mirrored by the attacker's [`FacingPosition`]
+    pub knockback: (f32, f32),
+    /// Sends grounded warriors flying, other hits only launch warriors already in the air
+    #[serde(default)]
+    pub launcher: bool,
+    pub hitstun_frames: u32,
+    pub blockstun_frames: u32,
+    /// Frames both warriors freeze when the move hits or is blocked
+    #[serde(default)]
+    pub hitstop_frames: u32,
+    #[serde(default)]
+    pub height: AttackHeight,
+    /// Damage taken even when blocked
+    #[serde(default)]
+    pub chip_damage: f32,
+    /// Makes it a throw, grabbing on the first active frame instead of hitting
+    #[serde(default)]
+    pub throw: Option<ThrowBox>,
+    /// Frames between hits on the same warrior, hits only once when `None`
+    #[serde(default)]
+    pub multi_hit_interval_frames: Option<u32>,
+    /// Shown across the whole move, keeps the position state sprite when empty
+    #[serde(default)]
+    pub sprite_indices: Vec<usize>,
+}
+
+impl MoveDefinition {
+    pub fn total_frames(&self) -> u32 {
+        self.startup_frames + self.active_frames + self.recovery_frames
+    }
+
+    pub fn sprite_index_at(&self, elapsed_frames: u32) -> Option<usize> {
+        if self.sprite_indices.is_empty() {
+            return None;
+        }
+
+        let sprite_position = elapsed_frames as usize * self.sprite_indices.len()
+            / self.total_frames().max(1) as usize;
+
+        Some(self.sprite_indices[sprite_position.min(self.sprite_indices.len() - 1)])
+    }
+}
+
+#[derive(Debug, Asset, TypePath, Deserialize)]
+pub struct MoveList {
+    pub moves: Vec<MoveDefinition>,
+}
+
+impl MoveList {
+    /// Specials of the completed motions come first, in the motions order
+    pub fn find_by_input(&self, input: &MoveInput, motions: &[Motion]) -> Option<&MoveDefinition> {
+        motions.iter().map(Some).chain([None]).find_map(|motion| {
+            self.moves.iter().find(|move_definition| {
+                move_definition.input == *input && move_definition.motion.as_ref() == motion
+            })
+        })
+    }
+}
+
+#[derive(Debug, Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct WarriorMoves(pub Handle<MoveList>);
+
+/// Move being performed, the warrior can't start another one until its recovery is over
+#[derive(Debug, Component, Reflect)]
+pub struct CurrentMove {
+    pub definition: MoveDefinition,
+    pub elapsed_frames: u32,
+    pub frame_timer: Timer,
+    pub spawned_hitboxes: usize,
+    pub throw_attempted: bool,
+}
+
+impl CurrentMove {
+    pub fn new(mut definition: MoveDefinition) -> Self {
+        // hitboxes are spawned in order while advancing frames
+        definition.hitboxes.sort_by_key(|hitbox| hitbox.start_frame);
+
+        CurrentMove {
+            definition,
+            elapsed_frames: 0,
+            frame_timer: Timer::from_seconds(FRAME_DURATION, TimerMode::Repeating),
+            spawned_hitboxes: 0,
+            throw_attempted: false,
+        }
+    }
+}
+
+pub fn start_warriors_moves(
+    mut commands: Commands,
+    mut attack_triggered_events: EventReader<AttackTriggered>,
+    mut warriors: Query<(&WarriorMoves, &mut InputBuffer, Has<CurrentMove>), &Warrior>,
+    move_lists: Res<Assets<MoveList>>,
+) {
+    for attack_triggered in attack_triggered_events.read() {
+        let Ok((warrior_moves, mut input_buffer, is_performing_move)) =
+            warriors.get_mut(attack_triggered.warrior_entity)
+        else {
+            continue;
+        };
+
+        if is_performing_move {
+            continue;
+        }
+
+        let Some(move_definition) = move_lists.get(&warrior_moves.0).and_then(|move_list| {
+            move_list.find_by_input(&attack_triggered.input, &attack_triggered.motions)
+        }) else {
+            continue;
+        };
+
+        if move_definition.motion.is_some() {
+            input_buffer.consume_motions();
+        }
+
+        commands
+            .entity(attack_triggered.warrior_entity)
+            .insert(CurrentMove::new(move_definition.clone()));
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn advance_warriors_moves(
+    mut commands: Commands,
+    mut warriors: Query<
+        (
+            Entity,
+            &mut CurrentMove,
+            &Transform,
+            &FacingPosition,
+            &AttackParty,
+            &DamagedState,
+        ),
+        (&Warrior, Without<Hitstop>),
+    >,
+    time: Res<Time>,
+    mut throw_attempted_events: EventWriter<ThrowAttempted>,
+) {
+    for (
+        //
+        warrior_entity,
+        mut current_move,
+        warrior_transform,
+        facing_position,
+        attack_party,
+        damaged_state,
+    ) in &mut warriors
+    {
+        // a hurt warrior's move is removed by the hit, this keeps it from advancing meanwhile
+        if *damaged_state != DamagedState::None {
+            continue;
+        }
+
+        current_move.frame_timer.tick(time.delta());
+        current_move.elapsed_frames += current_move.frame_timer.times_finished_this_tick();
+
+        let CurrentMove {
+            definition,
+            elapsed_frames,
+            spawned_hitboxes,
+            throw_attempted,
+            ..
+        } = current_move.as_mut();
+
+        if let Some(throw_box) = &definition.throw {
+            if !*throw_attempted && *elapsed_frames >= definition.startup_frames {
+                *throw_attempted = true;
+                throw_attempted_events.send(ThrowAttempted {
+                    warrior_entity,
+                    throw_box: throw_box.clone(),
+                });
+            }
+        }
+
+        while let Some(hitbox) = definition.hitboxes.get(*spawned_hitboxes) {
+            if definition.startup_frames + hitbox.start_frame > *elapsed_frames {
+                break;
+            }
+
+            *spawned_hitboxes += 1;
+
+            let offset = Vec2::new(
+                hitbox.offset.0 * facing_position.direction(),
+                hitbox.offset.1,
+            );
+
+            commands.spawn((
+                Name::new(format!("Attack ({})", definition.name)),
+                Attack {
+                    damage: definition.damage,
+                    stun: definition.stun,
+                    knockback: Vec2::new(
+                        definition.knockback.0 * facing_position.direction(),
+                        definition.knockback.1,
+                    ),
+                    launcher: definition.launcher,
+                    hitstun: definition.hitstun_frames as f32 * FRAME_DURATION,
+                    blockstun: definition.blockstun_frames as f32 * FRAME_DURATION,
+                    hitstop: definition.hitstop_frames as f32 * FRAME_DURATION,
+                    height: definition.height.clone(),
+                    chip_damage: definition.chip_damage,
+                    multi_hit_interval: definition
+                        .multi_hit_interval_frames
+                        .map(|frames| frames as f32 * FRAME_DURATION),
+                },
+                AttackHistory::default(),
+                AttackOwner {
+                    warrior_entity,
+                    offset,
+                },
+                attack_party.clone(),
+                AttackTimer {
+                    timer: Timer::from_seconds(
+                        hitbox.duration_frames as f32 * FRAME_DURATION,
+                        TimerMode::Once,
+                    ),
+                },
+                TransformBundle::from_transform(Transform::from_translation(
+                    warrior_transform.translation + offset.extend(0.),
+                )),
+                Collider::cuboid(hitbox.half_extents.0, hitbox.half_extents.1),
+                Sensor,
+                CollisionGroups::new(ATTACK_GROUP, HURTBOX_GROUP),
+                DespawnOnExit(AppState::Fight),
+            ));
+        }
+
+        if *elapsed_frames >= definition.total_frames() {
+            commands.entity(warrior_entity).remove::<CurrentMove>();
+        }
+    }
+}
@@ -1,12 +1,11 @@
-use bevy::prelude::*;
+use bevy::{ecs::query::Has, prelude::*};
 
 use crate::assets::IncrementSpriteIndex;
 
-use super::{DamagedState, UpdateWarriorHitbox, Warrior, WarriorPositionState};
-
-#[derive(Debug, Component, Reflect, Default)]
-#[reflect(Component)]
-pub struct Sprite;
+use super::{
+    CurrentMove, DamagedState, FacingPosition, Hitstop, Throwing, Thrown, UpdateWarriorHitbox,
+    Warrior, WarriorPositionState,
+};
 
 #[derive(Debug, Component, Reflect, Default)]
 #[reflect(Component)]
@@ -14,6 +13,7 @@ pub struct SpriteAnimationTimer {
     pub timer: Timer,
 }
 
+#[allow(clippy::type_complexity)]
 pub fn update_warriors_sprites(
     mut animated_sprites: Query<
         (
@@ -23,12 +23,20 @@ pub fn update_warriors_sprites(
             &DamagedState,
             &mut TextureAtlasSprite,
             Changed<WarriorPositionState>,
+            Changed<DamagedState>,
+            Option<&CurrentMove>,
+            Has<Hitstop>,
+            Has<Throwing>,
+            Has<Thrown>,
         ),
         &Warrior,
     >,
     time: Res<Time>,
     mut update_hitbox_event: EventWriter<UpdateWarriorHitbox>,
+    mut finished_moves: RemovedComponents<CurrentMove>,
 ) {
+    let finished_moves: Vec<Entity> = finished_moves.read().collect();
+
     for (
         //
         entity,
@@ -37,6 +45,11 @@ pub fn update_warriors_sprites(
         damaged_state,
         mut sprite_atlas,
         changed_position_state,
+        changed_damaged_state,
+        current_move,
+        is_in_hitstop,
+        is_throwing,
+        is_thrown,
     ) in &mut animated_sprites
     {
         if changed_position_state {
@@ -47,10 +60,37 @@ pub fn update_warriors_sprites(
             });
         }
 
-        sprite_animation_timer.timer.tick(time.delta());
+        if !is_in_hitstop {
+            sprite_animation_timer.timer.tick(time.delta());
+        }
+
+        if let Some(move_sprite_idx) = current_move.and_then(|current_move| {
+            current_move
+                .definition
+                .sprite_index_at(current_move.elapsed_frames)
+        }) {
+            sprite_atlas.index = move_sprite_idx;
+            continue;
+        }
+
+        // both warriors hold a single pose through the throw, see `grab_warriors`
+        let is_animation_locked = is_throwing || is_thrown;
 
-        if changed_position_state || sprite_animation_timer.timer.just_finished() {
+        if changed_position_state
+            || changed_damaged_state
+            || finished_moves.contains(&entity)
+            || (!is_animation_locked && sprite_animation_timer.timer.just_finished())
+        {
             sprite_atlas.update_sprite_idx(position_state, damaged_state);
         }
     }
 }
+
+/// Sprites are drawn facing right
+pub fn flip_warriors_sprites(
+    mut warriors: Query<(&FacingPosition, &mut TextureAtlasSprite), Changed<FacingPosition>>,
+) {
+    for (facing_position, mut sprite_atlas) in &mut warriors {
+        sprite_atlas.flip_x = *facing_position == FacingPosition::Left;
+    }
+}
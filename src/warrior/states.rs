@@ -2,6 +2,10 @@ use std::marker::PhantomData;
 
 use bevy::prelude::*;
 
+use crate::game_state::FightSet;
+
+use super::{AttackTimer, Hitstop, ThrowTimer};
+
 /// For timers only with Once timer mode
 ///
 /// TODO: transform into derive macro
@@ -16,13 +20,30 @@ pub trait RegisterStateTimerSystems {
 impl RegisterStateTimerSystems for App {
     fn register_state_timer_systems(&mut self) -> &mut Self {
         self.add_event::<StateTimerFinished<DamagedTimer>>()
-            .add_systems(Update, (tick_states_timers::<DamagedTimer>,))
+            .add_event::<StateTimerFinished<AttackTimer>>()
+            .add_event::<StateTimerFinished<StunnedTimer>>()
+            .add_event::<StateTimerFinished<FallenTimer>>()
+            .add_event::<StateTimerFinished<GettingUpTimer>>()
+            .add_event::<StateTimerFinished<ThrowTimer>>()
+            .add_systems(
+                Update,
+                (
+                    tick_states_timers::<DamagedTimer>,
+                    tick_states_timers::<AttackTimer>,
+                    tick_states_timers::<StunnedTimer>,
+                    tick_states_timers::<FallenTimer>,
+                    tick_states_timers::<GettingUpTimer>,
+                    tick_states_timers::<ThrowTimer>,
+                )
+                    .in_set(FightSet),
+            )
     }
 }
 
 pub fn tick_states_timers<T: StateTimer>(
     mut timer_finished_event: EventWriter<StateTimerFinished<T>>,
-    mut state_timers: Query<(Entity, &mut T)>,
+    // frozen by hitstop until it is over
+    mut state_timers: Query<(Entity, &mut T), Without<Hitstop>>,
     time: Res<Time>,
 ) {
     for (warrior_entity, mut timer) in &mut state_timers {
@@ -50,6 +71,7 @@ pub enum WarriorPositionState {
     Jumping,
     Crouching,
     Fallen,
+    GettingUp,
 }
 
 #[derive(Debug, Component, Clone, Default, Reflect, PartialEq, Eq)]
@@ -66,6 +88,16 @@ pub enum FacingPosition {
     Left,
 }
 
+impl FacingPosition {
+    /// Sign of the X axis the warrior is looking at
+    pub fn direction(&self) -> f32 {
+        match self {
+            FacingPosition::Right => 1.,
+            FacingPosition::Left => -1.,
+        }
+    }
+}
+
 #[derive(Debug, Component, Reflect, Default)]
 #[reflect(Component)]
 pub struct DamagedTimer {
@@ -78,11 +110,51 @@ impl StateTimer for DamagedTimer {
     }
 }
 
+#[derive(Debug, Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct StunnedTimer {
+    pub timer: Timer,
+}
+
+impl StateTimer for StunnedTimer {
+    fn get_timer(&mut self) -> &mut Timer {
+        &mut self.timer
+    }
+}
+
+/// Time lying down after a knockdown, which can be shortened or extended once
+#[derive(Debug, Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct FallenTimer {
+    pub timer: Timer,
+    pub rise_chosen: bool,
+}
+
+impl StateTimer for FallenTimer {
+    fn get_timer(&mut self) -> &mut Timer {
+        &mut self.timer
+    }
+}
+
+#[derive(Debug, Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct GettingUpTimer {
+    pub timer: Timer,
+}
+
+impl StateTimer for GettingUpTimer {
+    fn get_timer(&mut self) -> &mut Timer {
+        &mut self.timer
+    }
+}
+
 #[derive(Debug, Component, Clone, Default, Reflect, PartialEq, Eq)]
 #[reflect(Component)]
 pub enum DamagedState {
     #[default]
     None,
     Hit,
+    /// Blockstun, the guard is kept while it lasts
+    Blocked,
     Stunned,
 }
@@ -1,17 +1,108 @@
 use bevy::prelude::*;
+use serde::Deserialize;
 
-#[derive(Debug, Component, Clone, Reflect)]
+#[derive(Debug, Component, Clone, Copy, PartialEq, Eq, Reflect)]
 pub enum WarriorKind {
     Jotaile,
-    // Ed,
-    // Rod,
-    // Turi,
-    // Fred,
+    Ed,
+    Rod,
+    Turi,
+    Fred,
 }
 
-#[derive(Debug, Component, Reflect, Default)]
+impl WarriorKind {
+    /// Order of the roster on the character select
+    pub const ALL: [WarriorKind; 5] = [
+        WarriorKind::Jotaile,
+        WarriorKind::Ed,
+        WarriorKind::Rod,
+        WarriorKind::Turi,
+        WarriorKind::Fred,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WarriorKind::Jotaile => "Jotaile",
+            WarriorKind::Ed => "Ed",
+            WarriorKind::Rod => "Rod",
+            WarriorKind::Turi => "Turi",
+            WarriorKind::Fred => "Fred",
+        }
+    }
+}
+
+#[derive(Debug, Component, Clone, Reflect, Default, Deserialize)]
 #[reflect(Component)]
 pub struct Speed {
     pub walk: f32,
     pub jump: f32,
 }
+
+#[derive(Debug, Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Health { current: max, max }
+    }
+
+    pub fn is_knocked_out(&self) -> bool {
+        self.current <= 0.
+    }
+
+    /// Returns true only if this damage was the one that knocked the warrior out
+    pub fn take_damage(&mut self, damage: f32) -> bool {
+        if self.is_knocked_out() {
+            return false;
+        }
+
+        self.current = (self.current - damage).max(0.);
+        self.is_knocked_out()
+    }
+}
+
+/// Divides the knockback taken, 1 is the reference weight
+#[derive(Debug, Component, Reflect)]
+#[reflect(Component)]
+pub struct Weight(pub f32);
+
+impl Default for Weight {
+    fn default() -> Self {
+        Weight(1.)
+    }
+}
+
+#[derive(Debug, Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct StunMeter {
+    pub current: f32,
+    pub max: f32,
+    /// Amount recovered per second while not being hit
+    pub decay: f32,
+}
+
+impl StunMeter {
+    pub fn new(max: f32, decay: f32) -> Self {
+        StunMeter {
+            current: 0.,
+            max,
+            decay,
+        }
+    }
+
+    /// Returns true when the meter overflows, resetting it
+    pub fn add(&mut self, amount: f32) -> bool {
+        self.current += amount;
+
+        if self.current < self.max {
+            return false;
+        }
+
+        self.current = 0.;
+        true
+    }
+}
@@ -0,0 +1,481 @@
+use bevy::{ecs::query::Has, prelude::*};
+use bevy_rapier2d::prelude::*;
+use bevy_tnua::TnuaToggle;
+use serde::Deserialize;
+
+use crate::input::{InputBuffer, InputFrameCount, WarriorAction, BUFFERED_INPUT_FRAMES};
+
+use super::{
+    AttackParty, CurrentMove, DamagedState, DamagedTimer, FacingPosition, Guard, Health,
+    Invulnerable, Pushbox, StateTimer, StateTimerFinished, Warrior, WarriorKnockedDown,
+    WarriorKnockedOut, WarriorPositionState, WarriorPositionStateTransition, FRAME_DURATION,
+    PUSHBOX_GROUP,
+};
+
+/// Seconds both warriors recoil after a throw is teched
+pub const THROW_TECH_RECOIL_SECONDS: f32 = 0.3;
+pub const THROW_TECH_PUSHBACK: f32 = 250.;
+
+/// Grab checked on the first active frame of the move, against the opponents' pushboxes
+#[derive(Debug, Clone, Deserialize, Reflect)]
+pub struct ThrowBox {
+    pub half_extents: (f32, f32),
+    /// Relative to the warrior's center while facing right, the thrown warrior is held there
+    pub offset: (f32, f32),
+    pub damage: f32,
+    /// Counted from the grab, the thrown warrior escapes by pressing throw in them
+    pub tech_frames: u32,
+    pub hold_frames: u32,
+}
+
+#[derive(Event)]
+pub struct ThrowAttempted {
+    pub warrior_entity: Entity,
+    pub throw_box: ThrowBox,
+}
+
+/// On the thrower while both warriors are locked together
+#[derive(Debug, Component, Reflect)]
+#[reflect(Component)]
+pub struct Throwing {
+    pub thrown_entity: Entity,
+    pub damage: f32,
+    /// Already mirrored by the thrower's [`FacingPosition`]
+    pub hold_offset: Vec2,
+}
+
+impl Default for Throwing {
+    fn default() -> Self {
+        Throwing {
+            thrown_entity: Entity::PLACEHOLDER,
+            damage: 0.,
+            hold_offset: Vec2::ZERO,
+        }
+    }
+}
+
+#[derive(Debug, Component, Reflect)]
+#[reflect(Component)]
+pub struct Thrown {
+    pub thrower_entity: Entity,
+    pub tech_timer: Timer,
+}
+
+impl Default for Thrown {
+    fn default() -> Self {
+        Thrown {
+            thrower_entity: Entity::PLACEHOLDER,
+            tech_timer: Timer::default(),
+        }
+    }
+}
+
+/// Ends the throw when the thrown warrior didn't tech it
+#[derive(Debug, Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct ThrowTimer {
+    pub timer: Timer,
+}
+
+impl StateTimer for ThrowTimer {
+    fn get_timer(&mut self) -> &mut Timer {
+        &mut self.timer
+    }
+}
+
+/// A grab that found someone to throw, applied once every attempt of the frame is known
+struct Grab {
+    thrower_entity: Entity,
+    thrown_entity: Entity,
+    offset: Vec2,
+    throw_box: ThrowBox,
+}
+
+#[allow(clippy::type_complexity)]
+pub fn grab_warriors(
+    mut commands: Commands,
+    mut throw_attempted_events: EventReader<ThrowAttempted>,
+    throwers: Query<(&Transform, &FacingPosition, &AttackParty), &Warrior>,
+    mut throwables: Query<
+        (
+            &AttackParty,
+            &mut WarriorPositionState,
+            &mut WarriorPositionStateTransition,
+            &DamagedState,
+            Has<Invulnerable>,
+            Has<Thrown>,
+            Has<Throwing>,
+        ),
+        &Warrior,
+    >,
+    pushboxes: Query<&Parent, &Pushbox>,
+    rapier_context: Res<RapierContext>,
+) {
+    let mut grabs = Vec::new();
+
+    for throw_attempted in throw_attempted_events.read() {
+        let thrower_entity = throw_attempted.warrior_entity;
+        let throw_box = &throw_attempted.throw_box;
+        let Ok((thrower_transform, facing_position, thrower_party)) = throwers.get(thrower_entity)
+        else {
+            continue;
+        };
+
+        let offset = Vec2::new(
+            throw_box.offset.0 * facing_position.direction(),
+            throw_box.offset.1,
+        );
+
+        let mut thrown_entity = None;
+
+        rapier_context.intersections_with_shape(
+            thrower_transform.translation.truncate() + offset,
+            0.,
+            &Collider::cuboid(throw_box.half_extents.0, throw_box.half_extents.1),
+            QueryFilter::new()
+                .groups(CollisionGroups::new(PUSHBOX_GROUP, PUSHBOX_GROUP))
+                .exclude_rigid_body(thrower_entity),
+            |pushbox_entity| {
+                let Ok(pushbox_parent) = pushboxes.get(pushbox_entity) else {
+                    return true;
+                };
+                let warrior_entity = pushbox_parent.get();
+
+                let Ok((
+                    //
+                    party,
+                    position_state,
+                    _,
+                    damaged_state,
+                    is_invulnerable,
+                    is_thrown,
+                    is_throwing,
+                )) = throwables.get(warrior_entity)
+                else {
+                    return true;
+                };
+
+                // airborne, knocked down or hurt warriors can't be grabbed
+                let is_throwable = party != thrower_party
+                    && matches!(
+                        *position_state,
+                        WarriorPositionState::Idle
+                            | WarriorPositionState::Walking
+                            | WarriorPositionState::Crouching
+                    )
+                    && *damaged_state == DamagedState::None
+                    && !is_invulnerable
+                    && !is_thrown
+                    && !is_throwing;
+
+                if is_throwable {
+                    thrown_entity = Some(warrior_entity);
+                }
+
+                !is_throwable
+            },
+        );
+
+        if let Some(thrown_entity) = thrown_entity {
+            grabs.push(Grab {
+                thrower_entity,
+                thrown_entity,
+                offset,
+                throw_box: throw_box.clone(),
+            });
+        }
+    }
+
+    for grab in &grabs {
+        let Grab {
+            thrower_entity,
+            thrown_entity,
+            offset,
+            throw_box,
+        } = grab;
+
+        // warriors that grabbed each other on the same frame tech both throws
+        let is_mutual = grabs.iter().any(|other_grab| {
+            other_grab.thrower_entity == *thrown_entity
+                && other_grab.thrown_entity == *thrower_entity
+        });
+
+        if is_mutual {
+            let Ok((_, facing_position, _)) = throwers.get(*thrower_entity) else {
+                continue;
+            };
+
+            recoil_from_tech(
+                &mut commands,
+                *thrower_entity,
+                -facing_position.direction() * THROW_TECH_PUSHBACK,
+            );
+            continue;
+        }
+
+        commands
+            .entity(*thrower_entity)
+            .remove::<CurrentMove>()
+            .insert((
+                Throwing {
+                    thrown_entity: *thrown_entity,
+                    damage: throw_box.damage,
+                    hold_offset: *offset,
+                },
+                ThrowTimer {
+                    timer: Timer::from_seconds(
+                        throw_box.hold_frames as f32 * FRAME_DURATION,
+                        TimerMode::Once,
+                    ),
+                },
+            ));
+
+        // throws go through the guard
+        commands
+            .entity(*thrown_entity)
+            .remove::<(CurrentMove, Guard, DamagedTimer)>()
+            .insert((
+                Thrown {
+                    thrower_entity: *thrower_entity,
+                    tech_timer: Timer::from_seconds(
+                        throw_box.tech_frames as f32 * FRAME_DURATION,
+                        TimerMode::Once,
+                    ),
+                },
+                Invulnerable,
+                TnuaToggle::SenseOnly,
+                DamagedState::Hit,
+            ));
+
+        // both warriors stand in the throw pose until it's over, see `update_warriors_sprites`
+        for warrior_entity in [*thrower_entity, *thrown_entity] {
+            let Ok((_, mut position_state, mut position_state_transition, ..)) =
+                throwables.get_mut(warrior_entity)
+            else {
+                continue;
+            };
+
+            if *position_state != WarriorPositionState::Idle {
+                position_state_transition.previous = position_state.clone();
+                *position_state = WarriorPositionState::Idle;
+            }
+        }
+    }
+}
+
+/// Keeps the thrown warrior in the thrower's hands
+pub fn hold_thrown_warriors(
+    mut thrown_warriors: Query<(&Thrown, &mut Transform, &mut Velocity)>,
+    throwers: Query<(&Throwing, &Transform), Without<Thrown>>,
+) {
+    for (thrown, mut thrown_transform, mut thrown_velocity) in &mut thrown_warriors {
+        let Ok((throwing, thrower_transform)) = throwers.get(thrown.thrower_entity) else {
+            continue;
+        };
+
+        thrown_transform.translation =
+            thrower_transform.translation + throwing.hold_offset.extend(0.);
+        thrown_velocity.linvel = Vec2::ZERO;
+    }
+}
+
+pub fn tech_throws(
+    mut commands: Commands,
+    mut thrown_warriors: Query<(Entity, &mut Thrown, &mut InputBuffer)>,
+    warriors: Query<&FacingPosition>,
+    time: Res<Time>,
+    input_frame_count: Res<InputFrameCount>,
+) {
+    for (thrown_entity, mut thrown, mut input_buffer) in &mut thrown_warriors {
+        thrown.tech_timer.tick(time.delta());
+
+        if thrown.tech_timer.finished()
+            || !input_buffer.consume_pressed_within(
+                WarriorAction::Throw,
+                BUFFERED_INPUT_FRAMES,
+                input_frame_count.0,
+            )
+        {
+            continue;
+        }
+
+        let thrower_entity = thrown.thrower_entity;
+
+        commands
+            .entity(thrower_entity)
+            .remove::<(Throwing, ThrowTimer)>();
+        commands
+            .entity(thrown_entity)
+            .remove::<(Thrown, Invulnerable)>();
+
+        let Ok(thrower_facing) = warriors.get(thrower_entity) else {
+            continue;
+        };
+        let forward = thrower_facing.direction();
+
+        recoil_from_tech(
+            &mut commands,
+            thrower_entity,
+            -forward * THROW_TECH_PUSHBACK,
+        );
+        recoil_from_tech(&mut commands, thrown_entity, forward * THROW_TECH_PUSHBACK);
+    }
+}
+
+/// Both warriors recoil away from each other as if they blocked
+fn recoil_from_tech(commands: &mut Commands, warrior_entity: Entity, pushback: f32) {
+    let Some(mut warrior_commands) = commands.get_entity(warrior_entity) else {
+        return;
+    };
+
+    warrior_commands.remove::<CurrentMove>().insert((
+        Velocity::linear(Vec2::new(pushback, 0.)),
+        DamagedState::Blocked,
+        TnuaToggle::SenseOnly,
+        DamagedTimer {
+            timer: Timer::from_seconds(THROW_TECH_RECOIL_SECONDS, TimerMode::Once),
+        },
+    ));
+}
+
+pub fn throw_timer_finished(
+    mut commands: Commands,
+    mut throw_timer_finished_events: EventReader<StateTimerFinished<ThrowTimer>>,
+    throwers: Query<&Throwing>,
+    mut thrown_warriors: Query<(&mut Health, &mut DamagedState)>,
+    mut knocked_out_events: EventWriter<WarriorKnockedOut>,
+    mut knocked_down_events: EventWriter<WarriorKnockedDown>,
+) {
+    for throw_timer_finished_event in throw_timer_finished_events.read() {
+        let thrower_entity = throw_timer_finished_event.0;
+        commands
+            .entity(thrower_entity)
+            .remove::<(Throwing, ThrowTimer)>();
+
+        let Ok(throwing) = throwers.get(thrower_entity) else {
+            continue;
+        };
+        let warrior_entity = throwing.thrown_entity;
+
+        commands.entity(warrior_entity).remove::<Thrown>();
+
+        let Ok((mut health, mut damaged_state)) = thrown_warriors.get_mut(warrior_entity) else {
+            continue;
+        };
+
+        *damaged_state = DamagedState::None;
+
+        if health.take_damage(throwing.damage) {
+            knocked_out_events.send(WarriorKnockedOut { warrior_entity });
+        } else {
+            knocked_down_events.send(WarriorKnockedDown { warrior_entity });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spawn_test_warrior(app: &mut App, x: f32, party: AttackParty) -> Entity {
+        app.world
+            .spawn((
+                Warrior,
+                party,
+                FacingPosition::Right,
+                WarriorPositionState::Idle,
+                WarriorPositionStateTransition::default(),
+                DamagedState::None,
+                RigidBody::Fixed,
+                TransformBundle::from_transform(Transform::from_xyz(x, 0., 0.)),
+            ))
+            .with_children(|warrior| {
+                warrior.spawn((
+                    Pushbox,
+                    Collider::cuboid(10., 30.),
+                    CollisionGroups::new(PUSHBOX_GROUP, PUSHBOX_GROUP),
+                    TransformBundle::default(),
+                ));
+            })
+            .id()
+    }
+
+    fn throw_test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            HierarchyPlugin,
+            RapierPhysicsPlugin::<NoUserData>::default(),
+        ))
+        .add_event::<ThrowAttempted>()
+        .add_systems(Update, grab_warriors);
+        app
+    }
+
+    fn attempt_throw(app: &mut App, warrior_entity: Entity) {
+        app.world.send_event(ThrowAttempted {
+            warrior_entity,
+            throw_box: ThrowBox {
+                half_extents: (10., 10.),
+                offset: (10., 0.),
+                damage: 10.,
+                tech_frames: 7,
+                hold_frames: 30,
+            },
+        });
+    }
+
+    #[test]
+    fn grab_overlapping_warrior() {
+        let mut app = throw_test_app();
+
+        let thrower_entity = spawn_test_warrior(&mut app, 0., AttackParty::Ally);
+        let thrown_entity = spawn_test_warrior(&mut app, 15., AttackParty::Enemy);
+        app.world
+            .entity_mut(thrown_entity)
+            .insert(WarriorPositionState::Crouching);
+
+        // the colliders are only queryable once rapier has synced them
+        app.update();
+
+        attempt_throw(&mut app, thrower_entity);
+        app.update();
+
+        let thrown = app.world.get::<Thrown>(thrown_entity).unwrap();
+        assert_eq!(thrown.thrower_entity, thrower_entity);
+        assert!(app.world.get::<Throwing>(thrower_entity).is_some());
+        assert!(app.world.get::<Thrown>(thrower_entity).is_none());
+        assert_eq!(
+            app.world.get::<WarriorPositionState>(thrown_entity),
+            Some(&WarriorPositionState::Idle)
+        );
+    }
+
+    #[test]
+    fn tech_mutual_grab() {
+        let mut app = throw_test_app();
+
+        let left_entity = spawn_test_warrior(&mut app, 0., AttackParty::Ally);
+        let right_entity = spawn_test_warrior(&mut app, 15., AttackParty::Enemy);
+        app.world
+            .entity_mut(right_entity)
+            .insert(FacingPosition::Left);
+        app.update();
+
+        attempt_throw(&mut app, left_entity);
+        attempt_throw(&mut app, right_entity);
+        app.update();
+
+        for (warrior_entity, pushback) in [
+            (left_entity, -THROW_TECH_PUSHBACK),
+            (right_entity, THROW_TECH_PUSHBACK),
+        ] {
+            let warrior = app.world.entity(warrior_entity);
+            assert!(!warrior.contains::<Throwing>());
+            assert!(!warrior.contains::<Thrown>());
+            assert_eq!(warrior.get::<DamagedState>(), Some(&DamagedState::Blocked));
+            assert_eq!(warrior.get::<Velocity>().unwrap().linvel.x, pushback);
+        }
+    }
+}